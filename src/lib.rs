@@ -60,6 +60,25 @@
 //! let _ = Foo::demo(true);
 //! ```
 //!
+//! Expressions and types in attributes can also be written directly, without wrapping
+//! them in a string literal, which keeps syntax highlighting and precise error spans.
+//! The string form is still accepted, so a string value has to be quoted twice:
+//!
+//! ```rust
+//! # use derive_demo::Demo;
+//! #[derive(Demo)]
+//! struct Foo {
+//!     #[Demo(value = vec![1, 2])]
+//!     x: Vec<u8>,
+//!     #[Demo(value = "\"text\"")]
+//!     y: &'static str,
+//!     #[Demo(into_iter = (u8, char))]
+//!     z: Vec<(u8, char)>,
+//! }
+//!
+//! let _ = Foo::demo([(1, 'a')]);
+//! ```
+//!
 //! To make type conversion easier, `#[Demo(into)]` attribute changes the parameter type
 //! to `impl Into<T>`, and populates the field with `value.into()`:
 //!
@@ -74,6 +93,156 @@
 //! let _ = Foo::demo("Hello");
 //! ```
 //!
+//! Fields that are not part of the demo surface at all can be marked with `#[Demo(skip)]`.
+//! They are left out of the argument list and initialized with `Default::default()`, or
+//! with the expression given alongside it as `#[Demo(skip, value = "..")]`:
+//!
+//! ```rust
+//! # use derive_demo::Demo;
+//! #[derive(Demo)]
+//! struct Foo {
+//!     x: bool,
+//!     #[Demo(skip)]
+//!     cache: Vec<u8>,
+//!     #[Demo(skip, value = "7")]
+//!     generation: u32,
+//! }
+//!
+//! let _ = Foo::demo(true);
+//! ```
+//!
+//! Somewhere in between a required argument and a default, `#[Demo(optional)]` makes the
+//! parameter an `Option<T>`. Passing `None` falls back to `Default::default()`, or to the
+//! expression given as `#[Demo(optional = "..")]`:
+//!
+//! ```rust
+//! # use derive_demo::Demo;
+//! #[derive(Demo)]
+//! struct Foo {
+//!     #[Demo(optional)]
+//!     x: u8,
+//!     #[Demo(optional = "10")]
+//!     y: u32,
+//! }
+//!
+//! let _ = Foo::demo(Some(1), None);
+//! ```
+//!
+//! Parameters are named after their fields, and `f0`, `f1`, .. for tuple fields. A different
+//! name is given with `#[Demo(arg = "name")]`, or derived from the field type with a bare
+//! `#[Demo(arg)]` (`user_id` for a `UserId` field):
+//!
+//! ```rust
+//! # use derive_demo::Demo;
+//! struct UserId(u32);
+//!
+//! #[derive(Demo)]
+//! struct Session(#[Demo(arg)] UserId, #[Demo(arg = "token")] String);
+//!
+//! let _ = Session::demo(UserId(1), "secret".to_owned());
+//! ```
+//!
+//! Fields are initialized one after another in declaration order, and the expressions of
+//! `value` and `optional` can refer by name to the fields declared before them and to
+//! their constructor parameters:
+//!
+//! ```rust
+//! # use derive_demo::Demo;
+//! #[derive(Demo)]
+//! struct Foo {
+//!     #[Demo(into)]
+//!     data: Vec<u8>,
+//!     #[Demo(value = data.len())]
+//!     len: usize,
+//! }
+//!
+//! let _ = Foo::demo([1, 2, 3]);
+//! ```
+//!
+//! A reference to a field declared later, or to its parameter, is a compile error at the
+//! expression:
+//!
+//! ```compile_fail,E0425
+//! # use derive_demo::Demo;
+//! #[derive(Demo)]
+//! struct Foo {
+//!     #[Demo(value = n.is_some())]
+//!     flag: bool,
+//!     #[Demo(optional)]
+//!     n: u8,
+//! }
+//! ```
+//!
+//! Field options can be combined in one `#[Demo(..)]` attribute or spread over several
+//! of them. Options that contradict each other, such as `default` together with
+//! `value`, or `into` on a field that is not a constructor argument, are rejected at
+//! compile time.
+//!
+//! Instead of repeating `#[Demo(into)]` on every field, it can be put on the type itself,
+//! either as `#[Demo(into)]` for all fields or as `#[Demo(into_for = "String, PathBuf")]`
+//! for fields of the listed types only. Individual fields opt out with `#[Demo(no_into)]`:
+//!
+//! ```rust
+//! # use derive_demo::Demo;
+//! #[derive(Demo)]
+//! #[Demo(into_for = "String")]
+//! struct Foo {
+//!     x: String,
+//!     #[Demo(no_into)]
+//!     y: String,
+//!     z: u8,
+//! }
+//!
+//! let _ = Foo::demo("Hello", "World".to_owned(), 1);
+//! ```
+//!
+//! `#[Demo(into_generic)]` does the same through a dedicated generic parameter of the
+//! constructor (`fn demo<IntoX: Into<T>>(x: IntoX)`), which also works for fields whose
+//! type is itself a generic parameter. `#[Demo(from = "Type")]` takes a `Type` and
+//! converts it with `From`, while `#[Demo(as_ref)]` and `#[Demo(to_owned)]` accept
+//! `impl AsRef<B>` and `&B` respectively and store an owned copy. The borrowed type `B` is
+//! inferred for `String`, `PathBuf`, `OsString`, `CString` and `Vec<T>`, and can be given
+//! explicitly as `#[Demo(to_owned = "B")]`:
+//!
+//! ```rust
+//! # use derive_demo::Demo;
+//! #[derive(Demo)]
+//! struct Foo<T> {
+//!     #[Demo(into_generic)]
+//!     x: T,
+//!     #[Demo(from = "u8")]
+//!     y: u64,
+//!     #[Demo(as_ref)]
+//!     z: String,
+//!     #[Demo(to_owned)]
+//!     w: Vec<u8>,
+//! }
+//!
+//! let _ = Foo::<i64>::demo(1i32, 2, "Hello", &[1, 2, 3]);
+//! ```
+//!
+//! Fields behind a smart pointer take the pointee with `#[Demo(boxed)]`, `#[Demo(arc)]`
+//! and `#[Demo(rc)]`, which also wrap it in the `Mutex`, `RwLock`, `RefCell` or `Cell`
+//! inside the pointer, while `#[Demo(cow)]` takes a reference to borrow:
+//!
+//! ```rust
+//! # use derive_demo::Demo;
+//! use std::borrow::Cow;
+//! use std::sync::{Arc, Mutex};
+//!
+//! #[derive(Demo)]
+//! struct Node {
+//!     #[Demo(boxed)]
+//!     value: Box<u32>,
+//!     #[Demo(arc)]
+//!     shared: Arc<Mutex<Vec<u8>>>,
+//!     #[Demo(cow)]
+//!     name: Cow<'static, str>,
+//! }
+//!
+//! let _ = Node::demo(1, vec![2], "node");
+//! ```
+//!
 //! For iterators/collections, `#[Demo(into_iter = "T")]` attribute changes the parameter type
 //! to `impl IntoIterator<Item = T>`, and populates the field with `value.into_iter().collect()`:
 //!
@@ -89,6 +258,48 @@
 //! let _ = Foo::demo(Some(true));
 //! ```
 //!
+//! The item type can be left out for the standard collections (`Vec`, `VecDeque`,
+//! `LinkedList`, `HashSet`, `BTreeSet`, `BinaryHeap`, boxed slices, and `HashMap`/`BTreeMap`
+//! whose items are `(K, V)` pairs):
+//!
+//! ```rust
+//! # use derive_demo::Demo;
+//! # use std::collections::HashMap;
+//! #[derive(Demo)]
+//! struct Foo {
+//!     #[Demo(into_iter)]
+//!     x: Vec<bool>,
+//!     #[Demo(into_iter)]
+//!     y: HashMap<String, u8>,
+//! }
+//!
+//! let _ = Foo::demo([true, false], [("a".to_owned(), 1)]);
+//! ```
+//!
+//! Arrays, including those of const generic length, are filled from the iterator as well.
+//! The constructor panics with the name of the field unless the iterator yields exactly
+//! as many items as the array holds, or, with `#[Demo(len_check = "error")]`, returns an
+//! error converted from a `&'static str` (see `#[Demo(error = ..)]` below):
+//!
+//! ```rust
+//! # use derive_demo::Demo;
+//! #[derive(Demo)]
+//! struct Grid<const N: usize> {
+//!     #[Demo(into_iter)]
+//!     cells: [u8; N],
+//! }
+//!
+//! #[derive(Demo)]
+//! #[Demo(error = String)]
+//! struct Checked {
+//!     #[Demo(into_iter, len_check = "error")]
+//!     pair: [u8; 2],
+//! }
+//!
+//! let _ = Grid::<3>::demo(0..3);
+//! assert!(Checked::demo([1, 2, 3]).is_err());
+//! ```
+//!
 //! Generic types are supported; in particular, `PhantomData<T>` fields will be not
 //! included in the argument list and will be initialized automatically:
 //!
@@ -98,7 +309,7 @@
 //! use std::marker::PhantomData;
 //!
 //! #[derive(Demo)]
-//! struct Generic<'a, T: Default, P> {
+//! struct Generic<'a, T, P> {
 //!     x: &'a str,
 //!     y: PhantomData<P>,
 //!     #[Demo(default)]
@@ -108,6 +319,294 @@
 //! let _ = Generic::<i32, u8>::demo("Hello");
 //! ```
 //!
+//! Bounds that the constructor needs, such as `T: Default` above, are inferred from the
+//! fields and added to the constructor rather than required on the type. They are
+//! replaced with `#[Demo(bound = "T: Clone")]`, on the type for all of them or on a
+//! field for its own (`bound = ""` for none):
+//!
+//! ```rust
+//! # use derive_demo::Demo;
+//! #[derive(Demo)]
+//! #[Demo(bound = "T: Default + Clone")]
+//! struct Cached<T> {
+//!     #[Demo(default)]
+//!     value: T,
+//! }
+//!
+//! let _ = Cached::<String>::demo();
+//! ```
+//!
+//! Constructors for concrete instances of a generic type, which need no type annotations,
+//! are added with `#[Demo(for = "Generic<u32, ()>")]`, in place of the generic
+//! constructors, or with `#[Demo(for(ty = "Generic<u8, ()>", name = "demo_u8"))]` next
+//! to them under another name. Lifetime arguments may be left out:
+//!
+//! ```rust
+//! # use derive_demo::Demo;
+//! use std::marker::PhantomData;
+//!
+//! #[derive(Demo)]
+//! #[Demo(for = "Tagged<u32, ()>", for(ty = "Tagged<u8, ()>", name = "demo_u8"))]
+//! struct Tagged<'a, T, P> {
+//!     name: &'a str,
+//!     tag: PhantomData<P>,
+//!     #[Demo(default)]
+//!     value: T,
+//! }
+//!
+//! let _ = Tagged::demo("a");
+//! let _ = Tagged::demo_u8("b");
+//! ```
+//!
+//! The same goes for `PhantomPinned` and `()` fields. Other zero-sized markers, such as
+//! aliases of `PhantomData`, are marked with `#[Demo(phantom)]` and set to their default,
+//! while `#[Demo(not_phantom)]` turns a field back into an argument, for example a type
+//! of your own that is also named `PhantomData`:
+//!
+//! ```rust
+//! # use derive_demo::Demo;
+//! use std::marker::{PhantomData, PhantomPinned};
+//!
+//! type Marker<T> = PhantomData<T>;
+//!
+//! #[derive(Demo)]
+//! struct Pinned<T> {
+//!     x: u8,
+//!     #[Demo(phantom)]
+//!     marker: Marker<T>,
+//!     pinned: PhantomPinned,
+//! }
+//!
+//! let _ = Pinned::<String>::demo(1);
+//! ```
+//!
+//! Options that have no effect, such as `#[Demo(into)]` on a marker field or on a field
+//! whose type is a bare type parameter, or `#[Demo(into_iter)]` on a field that is not a
+//! collection, are reported as (deprecation) warnings at the field.
+//!
+//! A hook given as `#[Demo(init = "path")]` is called with `&mut Self` on every value the
+//! constructors build, for example to compute derived fields or normalise data;
+//! `#[Demo(init_with = "path")]` takes and returns `Self` instead. Together with
+//! `#[Demo(error = "E")]` the hook returns `Result<(), E>` (or `Result<Self, E>`) and the
+//! constructors become fallible, returning `Result<Self, E>`:
+//!
+//! ```rust
+//! # use derive_demo::Demo;
+//! #[derive(Demo)]
+//! #[Demo(init = Self::check, error = String)]
+//! struct Range {
+//!     start: u32,
+//!     end: u32,
+//! }
+//!
+//! impl Range {
+//!     fn check(&mut self) -> Result<(), String> {
+//!         if self.start > self.end {
+//!             return Err(format!("{} > {}", self.start, self.end));
+//!         }
+//!         Ok(())
+//!     }
+//! }
+//!
+//! assert!(Range::demo(1, 2).is_ok());
+//! assert!(Range::demo(2, 1).is_err());
+//! ```
+//!
+//! A single field is checked with `#[Demo(validate = path)]`, which calls `path` with a
+//! reference to the value of the field before the constructor builds the value. It can
+//! panic on invalid input, or, with `#[Demo(error = ..)]`, return `Result<(), E>`, whose
+//! error the constructor returns:
+//!
+//! ```rust
+//! # use derive_demo::Demo;
+//! fn non_empty(name: &String) -> Result<(), String> {
+//!     match name.is_empty() {
+//!         true => Err("empty name".to_owned()),
+//!         false => Ok(()),
+//!     }
+//! }
+//!
+//! #[derive(Demo)]
+//! #[Demo(error = String)]
+//! struct Account {
+//!     #[Demo(into, validate = non_empty)]
+//!     name: String,
+//! }
+//!
+//! assert!(Account::demo("ann").is_ok());
+//! assert!(Account::demo("").is_err());
+//! ```
+//!
+//! The parameters follow the declaration order of the fields by default. To keep the
+//! constructor signature stable when fields are reordered, the order can be given
+//! explicitly, either as a list `#[Demo(args = "b, a")]` on the struct (or on each enum
+//! variant, with indices for tuple fields) or as `#[Demo(order = N)]` keys on all
+//! argument fields:
+//!
+//! ```rust
+//! # use derive_demo::Demo;
+//! #[derive(Demo)]
+//! #[Demo(args = "name, id")]
+//! struct User {
+//!     id: u32,
+//!     name: &'static str,
+//! }
+//!
+//! #[derive(Demo)]
+//! struct Point {
+//!     #[Demo(order = 2)]
+//!     y: i32,
+//!     #[Demo(order = 1)]
+//!     x: i32,
+//! }
+//!
+//! let _ = User::demo("root", 0);
+//! let _ = Point::demo(1, 2);
+//! ```
+//!
+//! The generated constructors are documented with their parameters, taking the first
+//! paragraph of each field's doc comment (or the text of `#[Demo(doc = "..")]` on the
+//! field), the fields set without a parameter and an example call. Further text is
+//! appended with `#[Demo(doc = "..")]` on the type or enum variant, while
+//! `#[Demo(replace_doc = "..")]` replaces the generated docs altogether:
+//!
+//! ```rust
+//! # use derive_demo::Demo;
+//! #[derive(Demo)]
+//! #[Demo(doc = "Used by the examples.")]
+//! struct Config {
+//!     /// Name of the service.
+//!     name: &'static str,
+//!     #[Demo(value = 8080)]
+//!     port: u16,
+//! }
+//!
+//! let _ = Config::demo("example");
+//! ```
+//!
+//! Constructors are marked `#[must_use]`, unless disabled with `#[Demo(must_use = false)]`.
+//! A `#[deprecated]` attribute on the type or on an enum variant carries over to the
+//! constructors, as does the `#[cfg(..)]` of a variant. Any other attributes are added with
+//! `#[Demo(attrs = "#[inline] #[cold]")]`:
+//!
+//! ```rust
+//! # use derive_demo::Demo;
+//! #[derive(Demo)]
+//! #[Demo(attrs = "#[inline]")]
+//! enum Message {
+//!     Ping,
+//!     #[deprecated(note = "use `Ping`")]
+//!     Hello,
+//!     #[cfg(feature = "never")]
+//!     Never,
+//! }
+//!
+//! let _ = Message::demo_ping();
+//! ```
+//!
+//! With `#[Demo(wrap = "Box, Arc, Rc, Pin<Box>")]`, further constructors with the same
+//! parameters return the value in a smart pointer, named with a suffix of `_boxed`,
+//! `_arc`, `_rc` and `_pinned` respectively:
+//!
+//! ```rust
+//! # use derive_demo::Demo;
+//! #[derive(Demo)]
+//! #[Demo(wrap = "Box, Pin<Box>")]
+//! struct Task {
+//!     id: u32,
+//! }
+//!
+//! let _: Box<Task> = Task::demo_boxed(1);
+//! let _: std::pin::Pin<Box<Task>> = Task::demo_pinned(2);
+//! ```
+//!
+//! `#[Demo(parts)]` on a struct adds `into_demo_parts(self)`, which returns the values of
+//! the fields that `demo` takes as a tuple in the order of the parameters, together with
+//! `From` conversions from the struct to that tuple and back through `demo` (`TryFrom`
+//! for fallible constructors):
+//!
+//! ```rust
+//! # use derive_demo::Demo;
+//! #[derive(Demo)]
+//! #[Demo(parts)]
+//! struct Point {
+//!     x: i32,
+//!     #[Demo(optional)]
+//!     y: i32,
+//! }
+//!
+//! let (x, y) = Point::demo(1, None).into_demo_parts();
+//! let _ = Point::from((x, y));
+//! ```
+//!
+//! Fields marked `#[Demo(override)]` are the parameters of a further constructor,
+//! `demo_from`, which takes a base value to copy all other fields from, like `..base`
+//! but usable wherever the constructor is:
+//!
+//! ```rust
+//! # use derive_demo::Demo;
+//! #[derive(Demo)]
+//! struct User {
+//!     #[Demo(override)]
+//!     id: u32,
+//!     #[Demo(override, into)]
+//!     name: String,
+//!     admin: bool,
+//! }
+//!
+//! let root = User::demo(0, "root", true);
+//! let _ = User::demo_from(root, 1, "alice");
+//! ```
+//!
+//! `#[Demo(patch)]` on a struct generates a `<Name>DemoPatch` struct with an `Option` of
+//! each field but the skipped and marker ones, implementing `Default`, so fixtures only
+//! spell out the fields they care about. `demo_patched` constructs a value from a patch,
+//! setting the fields that are `None` to their `#[Demo(value = ..)]`, the fallback of
+//! `#[Demo(optional = ..)]` or default, while `apply_demo_patch` sets the fields that are
+//! `Some` on an existing value. The types of the other fields, including the constructor
+//! arguments, have to implement `Default` for `demo_patched`:
+//!
+//! ```rust
+//! # use derive_demo::Demo;
+//! #[derive(Demo)]
+//! #[Demo(patch)]
+//! struct Person {
+//!     name: String,
+//!     #[Demo(value = 18)]
+//!     age: u8,
+//! }
+//!
+//! let mut person = Person::demo_patched(PersonDemoPatch {
+//!     name: Some("Ann".to_owned()),
+//!     ..Default::default()
+//! });
+//! assert_eq!(person.age, 18);
+//! person.apply_demo_patch(PersonDemoPatch {
+//!     age: Some(3),
+//!     ..Default::default()
+//! });
+//! assert_eq!(person.age, 3);
+//! ```
+//!
+//! Fields gated with `#[cfg(..)]` keep their `#[cfg(..)]` on the constructor parameter,
+//! so the signature depends on the configuration. With `#[Demo(cfg_variants)]`, `demo`
+//! instead sets those fields to their default (or to the fallback of `optional`) and a
+//! `demo_full` constructor, which exists only when all of them do, takes them as well:
+//!
+//! ```rust
+//! # use derive_demo::Demo;
+//! #[derive(Demo)]
+//! #[Demo(cfg_variants)]
+//! struct Server {
+//!     name: &'static str,
+//!     #[cfg(not(feature = "never"))]
+//!     port: u16,
+//! }
+//!
+//! let _ = Server::demo("example");
+//! let _ = Server::demo_full("example", 8080);
+//! ```
+//!
 //! For enums, one constructor method is generated for each variant, with the type
 //! name being converted to snake case; otherwise, all features supported for
 //! structs work for enum variants as well:
@@ -197,6 +696,7 @@ fn path_to_string(path: &syn::Path) -> String {
 
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
+use std::ops::Range;
 use syn::{punctuated::Punctuated, Attribute, Lit, Token, Visibility};
 
 #[proc_macro_derive(Demo, attributes(Demo))]
@@ -214,7 +714,7 @@ pub fn derive(input: TokenStream) -> TokenStream {
 fn demo_for_struct(
     ast: &syn::DeriveInput,
     fields: &syn::Fields,
-    variant: Option<&syn::Ident>,
+    variant: Option<&syn::Variant>,
     options: &DemoOptions,
 ) -> proc_macro2::TokenStream {
     match *fields {
@@ -236,11 +736,20 @@ fn demo_for_enum(
     if data.variants.is_empty() {
         panic!("#[derive(Demo)] cannot be implemented for enums with zero variants");
     }
+    if options.args.is_some() {
+        panic!("#[Demo(args = ..)] on an enum has to be given on each variant");
+    }
+    if options.parts {
+        panic!("#[Demo(parts)] cannot be used on enums");
+    }
+    if options.patch {
+        panic!("#[Demo(patch)] cannot be used on enums");
+    }
     let impls = data.variants.iter().map(|v| {
         if v.discriminant.is_some() {
             panic!("#[derive(Demo)] cannot be implemented for enums with discriminants");
         }
-        demo_for_struct(ast, &v.fields, Some(&v.ident), options)
+        demo_for_struct(ast, &v.fields, Some(v), options)
     });
     my_quote!(#(#impls)*)
 }
@@ -249,310 +758,2799 @@ fn demo_impl(
     ast: &syn::DeriveInput,
     fields: Option<&Punctuated<syn::Field, Token![,]>>,
     named: bool,
-    variant: Option<&syn::Ident>,
+    variant: Option<&syn::Variant>,
     options: &DemoOptions,
 ) -> proc_macro2::TokenStream {
     let name = &ast.ident;
-    let unit = fields.is_none();
+    let target = Target {
+        ast,
+        variant,
+        variant_options: variant.map(|v| VariantOptions::from_attributes(&v.attrs)),
+        named,
+        unit: fields.is_none(),
+        options,
+        prefix: "demo".to_owned(),
+        concrete: false,
+    };
     let empty = Default::default();
-    let fields: Vec<_> = fields
-        .unwrap_or(&empty)
+    let raw_fields = fields.unwrap_or(&empty);
+    let fields: Vec<_> = raw_fields
         .iter()
         .enumerate()
-        .map(|(i, f)| FieldExt::new(f, i, named))
+        .map(|(i, f)| FieldExt::new(f, i, named, &ast.generics, options))
         .collect();
-    let args = fields.iter().filter_map(|f| f.as_arg());
-    let inits = fields.iter().map(|f| f.as_init());
-    let inits = if unit {
-        my_quote!()
-    } else if named {
-        my_quote![{ #(#inits),* }]
-    } else {
-        my_quote![( #(#inits),* )]
-    };
-    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
-    let (mut demo, qual, doc) = match variant {
-        None => (
-            syn::Ident::new("demo", proc_macro2::Span::call_site()),
-            my_quote!(),
-            format!("Constructs a demo `{}`.", name),
-        ),
-        Some(ref variant) => (
-            syn::Ident::new(
-                &format!("demo_{}", to_snake_case(&variant.to_string())),
-                proc_macro2::Span::call_site(),
-            ),
-            my_quote!(::#variant),
-            format!("Constructs a demo `{}::{}`.", name, variant),
-        ),
-    };
-    demo.set_span(proc_macro2::Span::call_site());
-    let lint_attrs = collect_parent_lint_attrs(&ast.attrs);
-    let lint_attrs = my_quote![#(#lint_attrs),*];
-    let visibility = &options.visibility;
-    my_quote! {
-        impl #impl_generics #name #ty_generics #where_clause {
-            #[doc = #doc]
-            #lint_attrs
-            #visibility fn #demo(#(#args),*) -> Self {
-                #name #qual #inits
+    check_arg_names(&fields);
+    let impl_attrs = target.impl_attrs();
+    let mut impls = Vec::new();
+    // An instance under the default name takes the place of the generic constructors,
+    // which would clash with its own.
+    if options.instances.iter().all(|i| i.name.is_some()) {
+        let fns = target.constructors(&fields);
+        let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+        impls.push(my_quote! {
+            #impl_attrs
+            impl #impl_generics #name #ty_generics #where_clause {
+                #(#fns)*
             }
-        }
+        });
+    }
+    for instance in &options.instances {
+        let (impl_generics, self_ty, substitution) = instance.resolve(ast);
+        let instance_fields: Vec<_> = raw_fields
+            .iter()
+            .map(|f| substitution.field(f, named))
+            .collect();
+        let instance_fields: Vec<_> = instance_fields
+            .iter()
+            .enumerate()
+            .map(|(i, f)| FieldExt::new(f, i, named, &ast.generics, options))
+            .collect();
+        let target = Target {
+            ast,
+            variant,
+            variant_options: variant.map(|v| VariantOptions::from_attributes(&v.attrs)),
+            named,
+            unit: target.unit,
+            options,
+            prefix: match instance.name {
+                Some(ref name) => name.to_string(),
+                None => "demo".to_owned(),
+            },
+            concrete: true,
+        };
+        let fns = target.constructors(&instance_fields);
+        impls.push(my_quote! {
+            #impl_attrs
+            impl #impl_generics #self_ty {
+                #(#fns)*
+            }
+        });
+    }
+    if options.parts {
+        impls.push(target.parts(&fields));
+    }
+    if options.patch {
+        impls.push(target.patch(&fields));
+    }
+    let warnings = fields.iter().flat_map(|f| {
+        f.warnings.iter().map(move |warning| {
+            // Stable proc macros cannot emit warnings, but the use of a deprecated item
+            // spanned at the field reports the note of its deprecation there.
+            let warning_use = quote_spanned!(f.span => let _ = IneffectiveDemoAttribute;);
+            my_quote! {
+                const _: () = {
+                    #[deprecated(note = #warning)]
+                    struct IneffectiveDemoAttribute;
+                    #warning_use
+                };
+            }
+        })
+    });
+    my_quote! {
+        #(#impls)*
+        #(#warnings)*
     }
 }
 
-fn collect_parent_lint_attrs(attrs: &[syn::Attribute]) -> Vec<syn::Attribute> {
-    fn is_lint(item: &syn::Meta) -> bool {
-        if let syn::Meta::List(ref l) = *item {
-            let path = &l.path;
-            return path.is_ident("allow")
-                || path.is_ident("deny")
-                || path.is_ident("forbid")
-                || path.is_ident("warn");
+/// The struct or enum variant built by the generated constructors.
+struct Target<'a> {
+    ast: &'a syn::DeriveInput,
+    variant: Option<&'a syn::Variant>,
+    variant_options: Option<VariantOptions>,
+    named: bool,
+    unit: bool,
+    options: &'a DemoOptions,
+    /// The name of the constructors, or its first part for enum variants.
+    prefix: String,
+    /// Whether the constructors are those of a concrete instance of a generic type.
+    concrete: bool,
+}
+
+impl Target<'_> {
+    fn variant_attrs(&self) -> &[syn::Attribute] {
+        self.variant.map(|v| &v.attrs[..]).unwrap_or(&[])
+    }
+
+    /// Name of a constructor, `demo` or `demo_<variant>` followed by `suffix`.
+    fn fn_name(&self, suffix: &str) -> syn::Ident {
+        let name = match self.variant {
+            None => format!("{}{}", self.prefix, suffix),
+            Some(variant) => format!(
+                "{}_{}{}",
+                self.prefix,
+                to_snake_case(&variant.ident.to_string()),
+                suffix
+            ),
+        };
+        syn::Ident::new(&name, proc_macro2::Span::call_site())
+    }
+
+    /// The constructors taking the arguments of `fields`.
+    fn constructors(&self, fields: &[FieldExt]) -> Vec<TokenStream2> {
+        let cfg_args: Vec<_> = fields
+            .iter()
+            .filter(|f| f.as_arg().is_some() && !f.cfgs.is_empty())
+            .collect();
+        let arg_list = self.arg_list().map(<[_]>::to_vec);
+        let mut variants = Vec::new();
+        if !self.options.cfg_variants || cfg_args.is_empty() {
+            variants.push((fields.to_vec(), arg_list, "", None));
+        } else {
+            // Keep the signature of the main constructor the same in every configuration,
+            // and take the conditional fields in a second one that only exists when they do.
+            let stable: Vec<_> = fields
+                .iter()
+                .map(|f| match f.cfgs.is_empty() {
+                    true => f.clone(),
+                    false => f.without_arg(),
+                })
+                .collect();
+            let predicates = cfg_args.iter().flat_map(|f| f.cfg_predicates());
+            let cfg = my_quote!(#[cfg(all(#(#predicates),*))]);
+            // The stable constructor does not take the conditional fields listed in `args`.
+            let stable_args = arg_list.as_ref().map(|list| {
+                list.iter()
+                    .filter(|member| cfg_args.iter().all(|f| f.member != **member))
+                    .cloned()
+                    .collect()
+            });
+            variants.push((stable, stable_args, "", None));
+            variants.push((fields.to_vec(), arg_list, "_full", Some(cfg)));
+        }
+        let mut result = Vec::new();
+        for (fields, arg_list, suffix, cfg) in &variants {
+            let attrs: Vec<_> = cfg.iter().cloned().collect();
+            let arg_list = arg_list.as_deref();
+            let name = self.fn_name(suffix);
+            result.push(self.constructor(fields, arg_list, &name, &attrs, None));
+            for &wrapper in &self.options.wrap {
+                let name = self.fn_name(&format!("{}{}", suffix, wrapper.suffix()));
+                result.push(self.constructor(fields, arg_list, &name, &attrs, Some(wrapper)));
+            }
+        }
+        result.extend(self.update_constructor(fields));
+        result
+    }
+
+    /// The explicit order of the constructor arguments, of the variant or else of the type.
+    fn arg_list(&self) -> Option<&[syn::Member]> {
+        match self.variant_options {
+            Some(ref variant_options) => variant_options.args.as_deref(),
+            None => self.options.args.as_deref(),
+        }
+    }
+
+    /// Attributes of the generated impl blocks: `#[allow(deprecated)]` if the type or the
+    /// variant is deprecated, as the impls name it.
+    fn impl_attrs(&self) -> TokenStream2 {
+        if is_deprecated(&self.ast.attrs) || is_deprecated(self.variant_attrs()) {
+            my_quote!(#[allow(deprecated)])
+        } else {
+            my_quote!()
+        }
+    }
+
+    /// `demo_from`, taking a base value and the fields marked `#[Demo(override)]`, if any,
+    /// and copying the other fields from the base.
+    fn update_constructor(&self, fields: &[FieldExt]) -> Option<TokenStream2> {
+        let overrides: Vec<_> = fields
+            .iter()
+            .filter(|f| f.attr.override_)
+            .cloned()
+            .collect();
+        if overrides.is_empty() {
+            return None;
+        }
+        if self.variant.is_some() {
+            panic!("#[Demo(override)] cannot be used on enum variants");
+        }
+        if let Some(f) = overrides.iter().find(|f| f.as_arg().is_none()) {
+            panic!(
+                "#[Demo(override)] field `{}` is not a constructor argument",
+                f.ident
+            );
+        }
+        let options = self.options;
+        let ordered: Vec<_> = ordered_args(fields, options.args.as_deref())
+            .into_iter()
+            .filter(|f| f.attr.override_)
+            .collect();
+        let base = syn::Ident::new("base", proc_macro2::Span::mixed_site());
+        let args = ordered.iter().filter_map(|f| f.as_arg());
+        let fn_generics: Vec<_> = overrides.iter().filter_map(|f| f.as_generic()).collect();
+        let fn_generics = if fn_generics.is_empty() {
+            my_quote!()
+        } else {
+            my_quote![<#(#fn_generics),*>]
+        };
+        let lets = overrides.iter().filter_map(|f| f.as_let());
+        let validations = overrides
+            .iter()
+            .filter_map(|f| f.as_validation(options.error.is_some()));
+        let bounds = self.bounds(&overrides);
+        let where_clause = if bounds.is_empty() {
+            my_quote!()
+        } else {
+            my_quote!(where #(#bounds),*)
+        };
+        let name = &self.ast.ident;
+        let demo = self.fn_name("_from");
+        let lines: Vec<_> = ordered.iter().map(|f| f.arg_doc()).collect();
+        let names: Vec<_> = ordered.iter().map(|f| f.arg.to_string()).collect();
+        let doc = format!(
+            "Constructs a demo `{}` from `base`, with the fields marked `#[Demo(override)]` \
+             taken from the arguments and the others from `base`.\n\n# Arguments\n\n\
+             * `base` - The value to copy the other fields from.\n{}\n\n# Example\n\n\
+             ```ignore\nlet value = {}::{}(base{}){};\n```",
+            name,
+            lines.join("\n"),
+            name,
+            demo,
+            names.iter().map(|n| format!(", {}", n)).collect::<String>(),
+            if options.error.is_some() { "?" } else { "" }
+        );
+        let members = overrides.iter().map(|f| {
+            let cfgs = &f.cfgs;
+            let member = &f.member;
+            let ident = &f.ident;
+            match f.member {
+                syn::Member::Named(_) => my_quote!(#(#cfgs)* #ident),
+                syn::Member::Unnamed(_) => my_quote!(#(#cfgs)* #member: #ident),
+            }
+        });
+        let value = options.finish(my_quote!(#name { #(#members,)* ..#base }));
+        let ret = options.return_type(my_quote!(Self));
+        let lint_attrs = collect_parent_lint_attrs(&self.ast.attrs);
+        let forwarded_attrs = collect_forwarded_attrs(&self.ast.attrs, &[]);
+        let must_use = if options.must_use && options.error.is_none() {
+            my_quote!(#[must_use])
+        } else {
+            my_quote!()
+        };
+        let extra_attrs = &options.attrs;
+        let visibility = &options.visibility;
+        Some(my_quote! {
+            #[doc = #doc]
+            #(#lint_attrs)*
+            #(#forwarded_attrs)*
+            #must_use
+            #(#extra_attrs)*
+            #visibility fn #demo #fn_generics(#base: Self, #(#args),*) -> #ret #where_clause {
+                #(#lets)*
+                #(#validations)*
+                #value
+            }
+        })
+    }
+
+    /// The `<Name>DemoPatch` struct, with an `Option` of each field, and `demo_patched`
+    /// and `apply_demo_patch`, which set the fields that are `Some` in a patch.
+    fn patch(&self, fields: &[FieldExt]) -> TokenStream2 {
+        let name = &self.ast.ident;
+        let patch_name = syn::Ident::new(&format!("{}DemoPatch", name), name.span());
+        let visibility = &self.options.visibility;
+        let generics = &self.ast.generics;
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        let patched: Vec<_> = fields
+            .iter()
+            .filter(|f| f.marker().is_none() && !f.attr.skip)
+            .collect();
+        // Fields of marker types and skipped fields are left out, so a marker of its own keeps every
+        // parameter of the type in use.
+        let markers: Vec<_> = generics
+            .params
+            .iter()
+            .filter_map(|param| match *param {
+                syn::GenericParam::Lifetime(ref param) => {
+                    let lifetime = &param.lifetime;
+                    Some(my_quote!(&#lifetime ()))
+                }
+                syn::GenericParam::Type(ref param) => {
+                    let ident = &param.ident;
+                    Some(my_quote!(#ident))
+                }
+                syn::GenericParam::Const(_) => None,
+            })
+            .collect();
+        let marker = if markers.is_empty() {
+            None
+        } else {
+            Some(my_quote!(::core::marker::PhantomData<fn() -> (#(#markers,)*)>))
+        };
+        let patch_fields = patched.iter().map(|f| {
+            let cfgs = &f.cfgs;
+            let ty = f.ty;
+            let decl = my_quote!(#(#cfgs)* #visibility);
+            match f.member {
+                syn::Member::Named(ref ident) => {
+                    my_quote!(#decl #ident: ::core::option::Option<#ty>)
+                }
+                syn::Member::Unnamed(_) => my_quote!(#decl ::core::option::Option<#ty>),
+            }
+        });
+        let marker_field = marker.as_ref().map(|marker| match self.named {
+            true => my_quote!(#[doc(hidden)] #visibility _marker: #marker),
+            false => my_quote!(#[doc(hidden)] #visibility #marker),
+        });
+        let patch_fields: Vec<_> = patch_fields.chain(marker_field).collect();
+        let (definition, default) = if self.unit || patch_fields.is_empty() {
+            (my_quote!(;), my_quote!(#patch_name))
+        } else if self.named {
+            let members = patched.iter().map(|f| {
+                let cfgs = &f.cfgs;
+                let member = &f.member;
+                my_quote!(#(#cfgs)* #member: ::core::option::Option::None)
+            });
+            let marker_init = marker
+                .as_ref()
+                .map(|_| my_quote!(_marker: ::core::marker::PhantomData));
+            (
+                my_quote!(#where_clause { #(#patch_fields),* }),
+                my_quote!(#patch_name { #(#members,)* #marker_init }),
+            )
+        } else {
+            let members = patched.iter().enumerate().map(|(i, f)| {
+                let cfgs = &f.cfgs;
+                let member = syn::Member::Unnamed(i.into());
+                my_quote!(#(#cfgs)* #member: ::core::option::Option::None)
+            });
+            let marker_init = marker.as_ref().map(|_| {
+                let member = syn::Member::Unnamed(patched.len().into());
+                my_quote!(#member: ::core::marker::PhantomData)
+            });
+            (
+                my_quote!((#(#patch_fields),*) #where_clause;),
+                my_quote!(#patch_name { #(#members,)* #marker_init }),
+            )
+        };
+        let patch_member = |i: usize, f: &FieldExt| match f.member {
+            syn::Member::Named(_) => f.member.clone(),
+            syn::Member::Unnamed(_) => syn::Member::Unnamed(i.into()),
+        };
+        let value = syn::Ident::new("value", proc_macro2::Span::mixed_site());
+        let patch = syn::Ident::new("patch", proc_macro2::Span::mixed_site());
+        let mut patched_index = 0;
+        let lets: Vec<_> = fields
+            .iter()
+            .map(|f| {
+                let cfgs = &f.cfgs;
+                let ident = &f.ident;
+                let ty = f.ty;
+                if f.marker().is_some() || f.attr.skip {
+                    let init = f.as_init();
+                    return my_quote!(#(#cfgs)* let #ident: #ty = #init;);
+                }
+                let fallback = match (&f.attr.source, &f.attr.conversion) {
+                    (Some(Source::Value(value)), _)
+                    | (None, Some(Conversion::Optional(Some(value)))) => my_quote!(#value),
+                    _ => quote_spanned!(f.span => ::core::default::Default::default()),
+                };
+                let member = patch_member(patched_index, f);
+                patched_index += 1;
+                // Like in `demo`, the expressions of later fields can name the value by its
+                // parameter.
+                let (name, field_let) = match f.as_arg() {
+                    Some(_) if f.arg != f.ident => {
+                        let arg = &f.arg;
+                        (arg, Some(my_quote!(#(#cfgs)* let #ident: #ty = #arg;)))
+                    }
+                    _ => (ident, None),
+                };
+                my_quote! {
+                    #(#cfgs)*
+                    let #name: #ty = match #patch.#member {
+                        ::core::option::Option::Some(#value) => #value,
+                        ::core::option::Option::None => #fallback,
+                    };
+                    #field_let
+                }
+            })
+            .collect();
+        let applies = patched.iter().enumerate().map(|(i, f)| {
+            let cfgs = &f.cfgs;
+            let member = &f.member;
+            let patch_member = patch_member(i, f);
+            my_quote! {
+                #(#cfgs)*
+                if let ::core::option::Option::Some(#value) = #patch.#patch_member {
+                    self.#member = #value;
+                }
+            }
+        });
+        let bounds: Vec<_> = fields
+            .iter()
+            .filter(|f| {
+                f.marker().is_none()
+                    && !matches!(f.attr.source, Some(Source::Value(_)))
+                    && !matches!(f.attr.conversion, Some(Conversion::Optional(Some(_))))
+            })
+            .map(|f| f.ty)
+            .filter(|ty| mentions_type_params(&my_quote!(#ty), generics))
+            .map(|ty| my_quote!(#ty: ::core::default::Default))
+            .collect();
+        let mut patched_generics = generics.clone();
+        for bound in bounds {
+            let predicate: syn::WherePredicate = syn::parse2(bound).unwrap();
+            patched_generics
+                .make_where_clause()
+                .predicates
+                .push(predicate);
+        }
+        let patched_where_clause = patched_generics.split_for_impl().2;
+        let options = self.options;
+        let ret = options.return_type(my_quote!(Self));
+        let validations = fields
+            .iter()
+            .filter_map(|f| f.as_validation(options.error.is_some()));
+        let built = options.finish(self.literal(fields));
+        let demo_patched = self.fn_name("_patched");
+        let struct_doc = format!(
+            "Fields of a [`{0}`] for [`{0}::{1}`] and [`{0}::apply_demo_patch`] to set, \
+             `None` for those to leave.",
+            name, demo_patched
+        );
+        let patched_doc = format!(
+            "Constructs a demo `{}` with the fields that are `Some` in `patch`, setting the \
+             others to their `#[Demo(value = ..)]`, `#[Demo(optional = ..)]` fallback or \
+             default.",
+            name
+        );
+        let apply_doc = "Sets the fields that are `Some` in `patch`.";
+        let must_use = if options.must_use && options.error.is_none() {
+            my_quote!(#[must_use])
+        } else {
+            my_quote!()
+        };
+        let lint_attrs = collect_parent_lint_attrs(&self.ast.attrs);
+        let forwarded_attrs = collect_forwarded_attrs(&self.ast.attrs, &[]);
+        let extra_attrs = &options.attrs;
+        let impl_attrs = self.impl_attrs();
+        my_quote! {
+            #[doc = #struct_doc]
+            #(#lint_attrs)*
+            #visibility struct #patch_name #generics #definition
+
+            #impl_attrs
+            impl #impl_generics ::core::default::Default for #patch_name #ty_generics
+                #where_clause
+            {
+                fn default() -> Self {
+                    #default
+                }
+            }
+
+            #impl_attrs
+            impl #impl_generics #name #ty_generics #where_clause {
+                #[doc = #patched_doc]
+                #(#lint_attrs)*
+                #(#forwarded_attrs)*
+                #must_use
+                #(#extra_attrs)*
+                #visibility fn #demo_patched(#patch: #patch_name #ty_generics) -> #ret
+                    #patched_where_clause
+                {
+                    #(#lets)*
+                    #(#validations)*
+                    #built
+                }
+
+                #[doc = #apply_doc]
+                #visibility fn apply_demo_patch(&mut self, #patch: #patch_name #ty_generics) {
+                    #(#applies)*
+                }
+            }
+        }
+    }
+
+    /// `into_demo_parts`, returning the arguments of `demo` that build the value, and the
+    /// conversions between the value and the tuple of them, which go through `demo`.
+    fn parts(&self, fields: &[FieldExt]) -> TokenStream2 {
+        if self.options.instances.iter().any(|i| i.name.is_none()) {
+            panic!("#[Demo(parts)] requires the generic `demo` constructor");
+        }
+        let options = self.options;
+        let ordered: Vec<_> = ordered_args(fields, options.args.as_deref())
+            .into_iter()
+            .filter(|f| f.as_arg().is_some())
+            .collect();
+        for f in &ordered {
+            if !f.cfgs.is_empty() {
+                panic!(
+                    "#[Demo(parts)] does not support the #[cfg] field `{}`",
+                    f.ident
+                );
+            }
+        }
+        let tys: Vec<_> = ordered.iter().map(|f| f.ty).collect();
+        let idents: Vec<_> = ordered.iter().map(|f| &f.ident).collect();
+        let patterns = ordered.iter().map(|f| match f.member {
+            syn::Member::Named(ref ident) => my_quote!(#ident),
+            syn::Member::Unnamed(ref member) => {
+                let ident = &f.ident;
+                my_quote!(#member: #ident)
+            }
+        });
+        let args = ordered.iter().map(|f| f.as_part_arg());
+        let name = &self.ast.ident;
+        let demo = self.fn_name("");
+        let doc = format!(
+            "Returns the arguments that [`{}::{}`] takes to construct `self` again.",
+            name, demo
+        );
+        let visibility = &options.visibility;
+        let mut generics = self.ast.generics.clone();
+        for bound in self.bounds(fields) {
+            let predicate: syn::WherePredicate = syn::parse2(bound).unwrap();
+            generics.make_where_clause().predicates.push(predicate);
+        }
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        let (own_impl_generics, _, own_where_clause) = self.ast.generics.split_for_impl();
+        let parts = my_quote!((#(#tys,)*));
+        let impl_attrs = self.impl_attrs();
+        let from_parts = match options.error {
+            None => my_quote! {
+                #impl_attrs
+                impl #impl_generics ::core::convert::From<#parts> for #name #ty_generics
+                    #where_clause
+                {
+                    fn from((#(#idents,)*): #parts) -> Self {
+                        Self::#demo(#(#args),*)
+                    }
+                }
+            },
+            Some(ref error) => my_quote! {
+                #impl_attrs
+                impl #impl_generics ::core::convert::TryFrom<#parts> for #name #ty_generics
+                    #where_clause
+                {
+                    type Error = #error;
+
+                    fn try_from(
+                        (#(#idents,)*): #parts,
+                    ) -> ::core::result::Result<Self, #error> {
+                        Self::#demo(#(#args),*)
+                    }
+                }
+            },
+        };
+        my_quote! {
+            #impl_attrs
+            impl #own_impl_generics #name #ty_generics #own_where_clause {
+                #[doc = #doc]
+                #visibility fn into_demo_parts(self) -> #parts {
+                    let #name { #(#patterns,)* .. } = self;
+                    (#(#idents,)*)
+                }
+            }
+
+            #impl_attrs
+            impl #own_impl_generics ::core::convert::From<#name #ty_generics> for #parts
+                #own_where_clause
+            {
+                fn from(value: #name #ty_generics) -> Self {
+                    value.into_demo_parts()
+                }
+            }
+
+            #from_parts
+        }
+    }
+
+    /// The where-clause predicates of a constructor building `fields`. Concrete
+    /// instances need none, bounds on their fields hold or fail regardless.
+    fn bounds(&self, fields: &[FieldExt]) -> Vec<TokenStream2> {
+        if self.concrete {
+            return Vec::new();
+        }
+        let generics = &self.ast.generics;
+        // A container bound replaces the inferred bounds, but not those of field bounds.
+        let mut bounds: Vec<_> = match self.options.bound {
+            Some(ref bound) => bound
+                .iter()
+                .map(|predicate| my_quote!(#predicate))
+                .chain(
+                    fields
+                        .iter()
+                        .filter(|f| f.attr.bound.is_some())
+                        .flat_map(|f| f.bounds(generics)),
+                )
+                .collect(),
+            None => fields.iter().flat_map(|f| f.bounds(generics)).collect(),
+        };
+        let mut seen = Vec::new();
+        bounds.retain(|bound| {
+            let bound = bound.to_string();
+            let new = !seen.contains(&bound);
+            seen.push(bound);
+            new
+        });
+        bounds
+    }
+
+    /// The expression building the target from the bindings of `fields`.
+    fn literal(&self, fields: &[FieldExt]) -> TokenStream2 {
+        let name = &self.ast.ident;
+        let qual = match self.variant {
+            None => my_quote!(),
+            Some(variant) => {
+                let variant = &variant.ident;
+                my_quote!(::#variant)
+            }
+        };
+        let idents = fields.iter().map(|f| {
+            let cfgs = &f.cfgs;
+            let ident = &f.ident;
+            my_quote!(#(#cfgs)* #ident)
+        });
+        if self.unit {
+            my_quote!(#name #qual)
+        } else if self.named {
+            my_quote!(#name #qual { #(#idents),* })
+        } else if fields.iter().all(|f| f.cfgs.is_empty()) {
+            my_quote!(#name #qual ( #(#idents),* ))
+        } else {
+            // Arguments of a call cannot be conditional, but fields of a tuple struct
+            // expression written with braces can.
+            let fields = fields.iter().map(|f| {
+                let cfgs = &f.cfgs;
+                let member = &f.member;
+                let ident = &f.ident;
+                my_quote!(#(#cfgs)* #member: #ident)
+            });
+            my_quote!(#name #qual { #(#fields),* })
+        }
+    }
+
+    /// A constructor taking the arguments of `fields`, ordered by `arg_list`, with `attrs`
+    /// added to it.
+    fn constructor(
+        &self,
+        fields: &[FieldExt],
+        arg_list: Option<&[syn::Member]>,
+        demo: &syn::Ident,
+        attrs: &[TokenStream2],
+        wrapper: Option<Wrapper>,
+    ) -> TokenStream2 {
+        let options = self.options;
+        let ordered = ordered_args(fields, arg_list);
+        let args = ordered.iter().filter_map(|f| f.as_arg());
+        let fn_generics: Vec<_> = fields.iter().filter_map(|f| f.as_generic()).collect();
+        let fn_generics = if fn_generics.is_empty() {
+            my_quote!()
+        } else {
+            my_quote![<#(#fn_generics),*>]
+        };
+        let lets = fields.iter().filter_map(|f| f.as_let());
+        let validations = fields
+            .iter()
+            .filter_map(|f| f.as_validation(options.error.is_some()));
+        let bounds = self.bounds(fields);
+        let where_clause = if bounds.is_empty() {
+            my_quote!()
+        } else {
+            my_quote!(where #(#bounds),*)
+        };
+        let name = &self.ast.ident;
+        let summary = match self.variant {
+            None => format!("Constructs a demo `{}`", name),
+            Some(variant) => format!("Constructs a demo `{}::{}`", name, variant.ident),
+        };
+        let summary = match wrapper {
+            None => format!("{}.", summary),
+            Some(wrapper) => format!("{} in {}.", summary, wrapper.description()),
+        };
+        let doc_options = [
+            Some(&options.doc),
+            self.variant_options.as_ref().map(|v| &v.doc),
+        ];
+        let doc = match doc_options
+            .iter()
+            .flatten()
+            .rev()
+            .find_map(|d| d.replace.as_ref())
+        {
+            Some(replace) => replace.clone(),
+            None => constructor_doc(
+                summary,
+                &format!("{}::{}", name, demo),
+                &ordered,
+                fields,
+                options.error.is_some(),
+                doc_options
+                    .iter()
+                    .flatten()
+                    .filter_map(|d| d.append.as_deref()),
+            ),
+        };
+        let lint_attrs = collect_parent_lint_attrs(&self.ast.attrs);
+        let forwarded_attrs = collect_forwarded_attrs(&self.ast.attrs, self.variant_attrs());
+        let must_use = if options.must_use && options.error.is_none() {
+            my_quote!(#[must_use])
+        } else {
+            my_quote!()
+        };
+        let extra_attrs = &options.attrs;
+        let visibility = &options.visibility;
+        let (ret, value) = match wrapper {
+            None => (
+                options.return_type(my_quote!(Self)),
+                options.finish(self.literal(fields)),
+            ),
+            Some(wrapper) => (
+                options.return_type(wrapper.wrap_type()),
+                wrapper.wrap(
+                    options.finish(self.literal(fields)),
+                    options.error.is_some(),
+                ),
+            ),
+        };
+        my_quote! {
+            #[doc = #doc]
+            #(#lint_attrs)*
+            #(#forwarded_attrs)*
+            #(#attrs)*
+            #must_use
+            #(#extra_attrs)*
+            #visibility fn #demo #fn_generics(#(#args),*) -> #ret #where_clause {
+                #(#lets)*
+                #(#validations)*
+                #value
+            }
+        }
+    }
+}
+
+/// Attributes of the type and of the enum variant that carry over to the constructor:
+/// `#[deprecated]` from either, and the `#[cfg]` of the variant.
+fn collect_forwarded_attrs<'a>(
+    attrs: &'a [syn::Attribute],
+    variant_attrs: &'a [syn::Attribute],
+) -> Vec<&'a syn::Attribute> {
+    let deprecated = variant_attrs
+        .iter()
+        .filter(|a| a.path().is_ident("deprecated"))
+        .chain(attrs.iter().filter(|a| a.path().is_ident("deprecated")))
+        .take(1);
+    let cfg = variant_attrs.iter().filter(|a| a.path().is_ident("cfg"));
+    deprecated.chain(cfg).collect()
+}
+
+fn is_deprecated(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|a| a.path().is_ident("deprecated"))
+}
+
+fn collect_parent_lint_attrs(attrs: &[syn::Attribute]) -> Vec<syn::Attribute> {
+    fn is_lint(item: &syn::Meta) -> bool {
+        if let syn::Meta::List(ref l) = *item {
+            let path = &l.path;
+            return path.is_ident("allow")
+                || path.is_ident("deny")
+                || path.is_ident("forbid")
+                || path.is_ident("warn");
+        }
+        false
+    }
+
+    fn is_cfg_attr_lint(item: &syn::Meta) -> bool {
+        if let syn::Meta::List(ref l) = *item
+            && l.path.is_ident("cfg_attr")
+            && let Ok(nested) =
+                l.parse_args_with(Punctuated::<syn::Meta, Token![,]>::parse_terminated)
+        {
+            return nested.len() == 2 && is_lint(&nested[1]);
         }
         false
     }
 
-    fn is_cfg_attr_lint(item: &syn::Meta) -> bool {
-        if let syn::Meta::List(ref l) = *item {
-            if l.path.is_ident("cfg_attr") {
-                if let Ok(nested) =
-                    l.parse_args_with(Punctuated::<syn::Meta, Token![,]>::parse_terminated)
-                {
-                    return nested.len() == 2 && is_lint(&nested[1]);
-                }
+    attrs
+        .iter()
+        .filter(|a| is_lint(&a.meta) || is_cfg_attr_lint(&a.meta))
+        .cloned()
+        .collect()
+}
+
+struct DemoOptions {
+    visibility: Option<syn::Visibility>,
+    into: bool,
+    into_for: Vec<syn::Ident>,
+    init: Option<Hook>,
+    error: Option<TokenStream2>,
+    args: Option<Vec<syn::Member>>,
+    doc: DocOptions,
+    must_use: bool,
+    attrs: Vec<syn::Attribute>,
+    cfg_variants: bool,
+    /// Replaces the bounds inferred from the fields.
+    bound: Option<Vec<syn::WherePredicate>>,
+    instances: Vec<Instance>,
+    wrap: Vec<Wrapper>,
+    /// Whether to generate `into_demo_parts` and the conversions from and to the parts.
+    parts: bool,
+    /// Whether to generate the `<Name>DemoPatch` struct and the methods that use it.
+    patch: bool,
+}
+
+/// A smart pointer that further constructors return the value in, from
+/// `#[Demo(wrap = "Box, Arc, Rc, Pin<Box>")]`.
+#[derive(Clone, Copy)]
+enum Wrapper {
+    Boxed,
+    Arc,
+    Rc,
+    Pinned,
+}
+
+impl Wrapper {
+    fn parse(ty: &syn::Type) -> syn::Result<Wrapper> {
+        match my_quote!(#ty).to_string().replace(' ', "").as_str() {
+            "Box" => Ok(Wrapper::Boxed),
+            "Arc" => Ok(Wrapper::Arc),
+            "Rc" => Ok(Wrapper::Rc),
+            "Pin<Box>" => Ok(Wrapper::Pinned),
+            _ => Err(syn::Error::new_spanned(
+                ty,
+                "expected `Box`, `Arc`, `Rc` or `Pin<Box>`",
+            )),
+        }
+    }
+
+    /// Appended to the names of the constructors.
+    fn suffix(self) -> &'static str {
+        match self {
+            Wrapper::Boxed => "_boxed",
+            Wrapper::Arc => "_arc",
+            Wrapper::Rc => "_rc",
+            Wrapper::Pinned => "_pinned",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Wrapper::Boxed => "a `Box`",
+            Wrapper::Arc => "an `Arc`",
+            Wrapper::Rc => "an `Rc`",
+            Wrapper::Pinned => "a pinned `Box`",
+        }
+    }
+
+    /// The type of `Self` in the pointer.
+    fn wrap_type(self) -> TokenStream2 {
+        let alloc = alloc_crate();
+        match self {
+            Wrapper::Boxed => my_quote!(#alloc::boxed::Box<Self>),
+            Wrapper::Arc => my_quote!(#alloc::sync::Arc<Self>),
+            Wrapper::Rc => my_quote!(#alloc::rc::Rc<Self>),
+            Wrapper::Pinned => my_quote!(::core::pin::Pin<#alloc::boxed::Box<Self>>),
+        }
+    }
+
+    /// Puts the result of a constructor, `value`, in the pointer.
+    fn wrap(self, value: TokenStream2, fallible: bool) -> TokenStream2 {
+        let alloc = alloc_crate();
+        let new = match self {
+            Wrapper::Boxed => my_quote!(#alloc::boxed::Box::new),
+            Wrapper::Arc => my_quote!(#alloc::sync::Arc::new),
+            Wrapper::Rc => my_quote!(#alloc::rc::Rc::new),
+            Wrapper::Pinned => my_quote!(#alloc::boxed::Box::pin),
+        };
+        if fallible {
+            my_quote!(::core::result::Result::map(#value, #new))
+        } else {
+            my_quote!(#new(#value))
+        }
+    }
+}
+
+/// A concrete instantiation of a generic type given constructors of its own, from
+/// `#[Demo(for = "Type<u32>")]` or `#[Demo(for(ty = "Type<u32>", name = "demo_u32"))]`.
+struct Instance {
+    ty: syn::Type,
+    /// Replaces `demo` in the names of the constructors.
+    name: Option<syn::Ident>,
+}
+
+impl Instance {
+    fn parse(meta: &syn::meta::ParseNestedMeta) -> syn::Result<Instance> {
+        if meta.input.peek(Token![=]) {
+            let value: syn::LitStr = meta.value()?.parse()?;
+            return Ok(Instance {
+                ty: value.parse()?,
+                name: None,
+            });
+        }
+        let mut ty = None;
+        let mut name = None;
+        meta.parse_nested_meta(|meta| {
+            if meta.path.is_ident("ty") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                ty = Some(value.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("name") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                name = Some(value.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported attribute"))
+            }
+        })?;
+        match ty {
+            Some(ty) => Ok(Instance { ty, name }),
+            None => Err(meta.error("expected `ty = \"..\"`")),
+        }
+    }
+
+    /// The generics and self type of the impl block of the instance, and the substitution
+    /// of the parameters of the type with its arguments. Lifetime arguments may be left
+    /// out, in which case the lifetime parameters stay generic.
+    fn resolve(&self, ast: &syn::DeriveInput) -> (TokenStream2, TokenStream2, Substitution) {
+        let name = &ast.ident;
+        let ty = &self.ty;
+        let segment = match last_path_segment(ty) {
+            Some(segment) if segment.ident == *name => segment,
+            _ => panic!(
+                "#[Demo(for = ..)] expects an instance of `{}`, found `{}`",
+                name,
+                my_quote!(#ty)
+            ),
+        };
+        let args: Vec<_> = match segment.arguments {
+            syn::PathArguments::AngleBracketed(ref args) => args.args.iter().collect(),
+            _ => Vec::new(),
+        };
+        let params: Vec<_> = ast.generics.params.iter().collect();
+        let lifetimes: Vec<_> = ast.generics.lifetimes().collect();
+        let (params, kept) = if args.len() == params.len() {
+            (params, Vec::new())
+        } else if args.len() + lifetimes.len() == params.len() {
+            let params = params
+                .into_iter()
+                .filter(|p| !matches!(p, syn::GenericParam::Lifetime(_)))
+                .collect();
+            (params, lifetimes)
+        } else {
+            panic!(
+                "#[Demo(for = ..)] expects {} generic arguments for `{}`, found {}",
+                params.len() - lifetimes.len(),
+                name,
+                args.len()
+            );
+        };
+        let mut substitution = Substitution::default();
+        for (param, arg) in params.iter().zip(&args) {
+            match (param, arg) {
+                (syn::GenericParam::Lifetime(param), syn::GenericArgument::Lifetime(arg)) => {
+                    substitution
+                        .lifetimes
+                        .push((param.lifetime.ident.clone(), my_quote!(#arg)));
+                }
+                (syn::GenericParam::Type(param), syn::GenericArgument::Type(arg)) => {
+                    substitution
+                        .types
+                        .push((param.ident.clone(), my_quote!(#arg)));
+                }
+                (syn::GenericParam::Const(param), syn::GenericArgument::Const(arg)) => {
+                    substitution
+                        .types
+                        .push((param.ident.clone(), my_quote!(#arg)));
+                }
+                // A const argument that is a bare path, such as `N`, parses as a type.
+                (syn::GenericParam::Const(param), syn::GenericArgument::Type(arg)) => {
+                    substitution
+                        .types
+                        .push((param.ident.clone(), my_quote!(#arg)));
+                }
+                _ => panic!(
+                    "#[Demo(for = ..)] has a mismatched argument for `{}`: `{}`",
+                    my_quote!(#param),
+                    my_quote!(#arg)
+                ),
+            }
+        }
+        let lifetimes = kept.iter().map(|l| &l.lifetime);
+        let args = lifetimes
+            .clone()
+            .map(|l| my_quote!(#l))
+            .chain(args.iter().map(|a| my_quote!(#a)));
+        let impl_generics = if kept.is_empty() {
+            my_quote!()
+        } else {
+            my_quote!(<#(#kept),*>)
+        };
+        (impl_generics, my_quote!(#name<#(#args),*>), substitution)
+    }
+}
+
+/// Generic parameters of a type replaced with the arguments of an instance.
+#[derive(Default)]
+struct Substitution {
+    lifetimes: Vec<(syn::Ident, TokenStream2)>,
+    /// Type and const parameters.
+    types: Vec<(syn::Ident, TokenStream2)>,
+}
+
+impl Substitution {
+    /// A copy of `field` with the parameters replaced in its type and attributes.
+    fn field(&self, field: &syn::Field, named: bool) -> syn::Field {
+        use syn::parse::Parser;
+
+        let tokens = self.apply(my_quote!(#field));
+        let parsed = if named {
+            syn::Field::parse_named.parse2(tokens)
+        } else {
+            syn::Field::parse_unnamed.parse2(tokens)
+        };
+        parsed.unwrap_or_else(|e| panic!("Invalid #[Demo(for = ..)] argument: {}", e))
+    }
+
+    fn apply(&self, ts: TokenStream2) -> TokenStream2 {
+        use proc_macro2::{Delimiter, Group, TokenTree};
+
+        let tokens: Vec<TokenTree> = ts.into_iter().collect();
+        let mut result = Vec::new();
+        let mut i = 0;
+        while i < tokens.len() {
+            match tokens[i] {
+                TokenTree::Group(ref group) => {
+                    let mut replaced = Group::new(group.delimiter(), self.apply(group.stream()));
+                    replaced.set_span(group.span());
+                    result.push(TokenTree::Group(replaced));
+                }
+                TokenTree::Punct(ref p) if p.as_char() == '\'' => {
+                    let lifetime = match tokens.get(i + 1) {
+                        Some(TokenTree::Ident(ident)) => {
+                            self.lifetimes.iter().find(|(param, _)| param == ident)
+                        }
+                        _ => None,
+                    };
+                    match lifetime {
+                        Some((_, arg)) => {
+                            result.extend(arg.clone());
+                            i += 1;
+                        }
+                        None => result.push(tokens[i].clone()),
+                    }
+                }
+                TokenTree::Ident(ref ident) => {
+                    // Parameters are never the later segments of a path.
+                    let path_segment = i >= 2
+                        && matches!(tokens[i - 1], TokenTree::Punct(ref p) if p.as_char() == ':')
+                        && matches!(tokens[i - 2], TokenTree::Punct(ref p) if p.as_char() == ':');
+                    match self.types.iter().find(|(param, _)| param == ident) {
+                        Some((_, arg)) if !path_segment => {
+                            let group = Group::new(Delimiter::None, arg.clone());
+                            result.push(TokenTree::Group(group));
+                        }
+                        _ => result.push(tokens[i].clone()),
+                    }
+                }
+                _ => result.push(tokens[i].clone()),
+            }
+            i += 1;
+        }
+        result.into_iter().collect()
+    }
+}
+
+/// Text added to the generated constructor docs.
+#[derive(Default)]
+struct DocOptions {
+    /// `doc = ".."`, appended to the generated docs.
+    append: Option<String>,
+    /// `replace_doc = ".."`, used instead of the generated docs.
+    replace: Option<String>,
+}
+
+impl DocOptions {
+    /// Parses the `doc` and `replace_doc` options, returning `false` for other options.
+    fn parse(&mut self, meta: &syn::meta::ParseNestedMeta) -> syn::Result<bool> {
+        let slot = if meta.path.is_ident("doc") {
+            &mut self.append
+        } else if meta.path.is_ident("replace_doc") {
+            &mut self.replace
+        } else {
+            return Ok(false);
+        };
+        let value: syn::LitStr = meta.value()?.parse()?;
+        *slot = Some(value.value());
+        Ok(true)
+    }
+}
+
+/// A function called on every value built by the generated constructors.
+enum Hook {
+    /// `fn(&mut Self)`, or `fn(&mut Self) -> Result<(), E>` with `error = "E"`.
+    Mut(TokenStream2),
+    /// `fn(Self) -> Self`, or `fn(Self) -> Result<Self, E>` with `error = "E"`.
+    Map(TokenStream2),
+}
+
+impl DemoOptions {
+    fn from_attributes(attrs: &[Attribute]) -> Self {
+        // Default visibility is public
+        let mut visibility = Some(Visibility::Public(syn::token::Pub {
+            span: proc_macro2::Span::call_site(),
+        }));
+        let mut into = false;
+        let mut into_for = Vec::new();
+        let mut init = None;
+        let mut error = None;
+        let mut args = None;
+        let mut doc = DocOptions::default();
+        let mut must_use = true;
+        let mut extra_attrs = Vec::new();
+        let mut cfg_variants = false;
+        let mut bound = None;
+        let mut instances = Vec::new();
+        let mut wrap = Vec::new();
+        let mut parts = false;
+        let mut patch = false;
+
+        for attr in attrs {
+            if attr.path().is_ident("Demo") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("visibility") {
+                        let value: Lit = meta.value()?.parse()?;
+                        if let Lit::Str(lit_str) = value {
+                            // Parse the visibility string into a syn::Visibility type
+                            let parsed_visibility: Visibility =
+                                lit_str.parse().expect("Invalid visibility");
+                            visibility = Some(parsed_visibility);
+                        }
+                        Ok(())
+                    } else if meta.path.is_ident("into") {
+                        into = true;
+                        Ok(())
+                    } else if meta.path.is_ident("into_for") {
+                        let value: syn::LitStr = meta.value()?.parse()?;
+                        let types = value.parse_with(
+                            Punctuated::<syn::Type, Token![,]>::parse_separated_nonempty,
+                        )?;
+                        for ty in types {
+                            match last_path_segment(&ty) {
+                                Some(segment) => into_for.push(segment.ident.clone()),
+                                None => return Err(meta.error("expected a list of type paths")),
+                            }
+                        }
+                        Ok(())
+                    } else if meta.path.is_ident("init") || meta.path.is_ident("init_with") {
+                        if init.is_some() {
+                            return Err(meta.error("only one of `init` and `init_with` is allowed"));
+                        }
+                        let hook = parse_expr_value(&meta)?;
+                        init = Some(if meta.path.is_ident("init") {
+                            Hook::Mut(hook)
+                        } else {
+                            Hook::Map(hook)
+                        });
+                        Ok(())
+                    } else if meta.path.is_ident("error") {
+                        error = Some(parse_type_value(&meta)?);
+                        Ok(())
+                    } else if meta.path.is_ident("args") {
+                        args = Some(parse_args_value(&meta)?);
+                        Ok(())
+                    } else if meta.path.is_ident("bound") {
+                        bound = Some(parse_bound_value(&meta)?);
+                        Ok(())
+                    } else if meta.path.is_ident("wrap") {
+                        let value: syn::LitStr = meta.value()?.parse()?;
+                        let types = value
+                            .parse_with(Punctuated::<syn::Type, Token![,]>::parse_terminated)?;
+                        for ty in types {
+                            wrap.push(Wrapper::parse(&ty)?);
+                        }
+                        Ok(())
+                    } else if meta.path.is_ident("for") {
+                        instances.push(Instance::parse(&meta)?);
+                        Ok(())
+                    } else if doc.parse(&meta)? {
+                        Ok(())
+                    } else if meta.path.is_ident("patch") {
+                        patch = true;
+                        Ok(())
+                    } else if meta.path.is_ident("parts") {
+                        parts = true;
+                        Ok(())
+                    } else if meta.path.is_ident("cfg_variants") {
+                        cfg_variants = true;
+                        Ok(())
+                    } else if meta.path.is_ident("must_use") {
+                        let value: syn::LitBool = meta.value()?.parse()?;
+                        must_use = value.value;
+                        Ok(())
+                    } else if meta.path.is_ident("attrs") {
+                        let value: syn::LitStr = meta.value()?.parse()?;
+                        extra_attrs.extend(value.parse_with(Attribute::parse_outer)?);
+                        Ok(())
+                    } else {
+                        Err(meta.error("unsupported attribute"))
+                    }
+                })
+                .unwrap_or_else(|err| panic!("Invalid #[Demo] attribute: {}", err));
+            }
+        }
+
+        DemoOptions {
+            visibility,
+            into,
+            into_for,
+            init,
+            error,
+            args,
+            doc,
+            must_use,
+            attrs: extra_attrs,
+            cfg_variants,
+            bound,
+            instances,
+            wrap,
+            parts,
+            patch,
+        }
+    }
+
+    /// The return type of the generated constructors, which build a `value` of that type.
+    fn return_type(&self, value: TokenStream2) -> TokenStream2 {
+        match self.error {
+            Some(ref error) => my_quote!(::core::result::Result<#value, #error>),
+            None => value,
+        }
+    }
+
+    /// Runs the `init` hook, if any, on the freshly built `value` and returns it in the
+    /// form given by `return_type`.
+    fn finish(&self, value: TokenStream2) -> TokenStream2 {
+        let result = syn::Ident::new("result", proc_macro2::Span::mixed_site());
+        match (&self.init, &self.error) {
+            (None, None) => value,
+            (None, Some(_)) => my_quote!(::core::result::Result::Ok(#value)),
+            (Some(Hook::Mut(hook)), None) => my_quote!({
+                let mut #result = #value;
+                #hook(&mut #result);
+                #result
+            }),
+            (Some(Hook::Mut(hook)), Some(_)) => my_quote!({
+                let mut #result = #value;
+                #hook(&mut #result)?;
+                ::core::result::Result::Ok(#result)
+            }),
+            (Some(Hook::Map(hook)), _) => my_quote!(#hook(#value)),
+        }
+    }
+
+    /// Whether a container-level `into` option applies to a field of type `ty`.
+    fn applies_into_to(&self, ty: &syn::Type) -> bool {
+        self.into
+            || last_path_segment(ty)
+                .map(|segment| self.into_for.contains(&segment.ident))
+                .unwrap_or(false)
+    }
+}
+
+/// Options of an enum variant, from its `#[Demo(..)]` attributes.
+#[derive(Default)]
+struct VariantOptions {
+    args: Option<Vec<syn::Member>>,
+    doc: DocOptions,
+}
+
+impl VariantOptions {
+    fn from_attributes(attrs: &[Attribute]) -> Self {
+        let mut result = VariantOptions::default();
+        for attr in attrs {
+            if attr.path().is_ident("Demo") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("args") {
+                        result.args = Some(parse_args_value(&meta)?);
+                        Ok(())
+                    } else if result.doc.parse(&meta)? {
+                        Ok(())
+                    } else {
+                        Err(meta.error("unsupported attribute"))
+                    }
+                })
+                .unwrap_or_else(|err| panic!("Invalid #[Demo] attribute: {}", err));
+            }
+        }
+        result
+    }
+}
+
+/// Options of a single field, merged from all of its `#[Demo(..)]` attributes.
+#[derive(Clone, Default)]
+struct FieldAttr {
+    conversion: Option<Conversion>,
+    source: Option<Source>,
+    skip: bool,
+    no_into: bool,
+    order: Option<i64>,
+    arg: Option<ArgName>,
+    doc: Option<String>,
+    /// Set by `phantom` (`true`) or `not_phantom` (`false`), else detected from the type.
+    phantom: Option<bool>,
+    /// Replaces the bounds inferred from the field.
+    bound: Option<Vec<syn::WherePredicate>>,
+    len_check: Option<LenCheck>,
+    /// The argument of `value`, `optional = ..` or `from` as written, for the docs.
+    written: Option<String>,
+    /// Called with a reference to the value of the field before the constructor builds
+    /// the target.
+    validate: Option<proc_macro2::TokenStream>,
+    /// Taken by `demo_from`, which copies the other fields from a base value.
+    override_: bool,
+}
+
+/// What the constructor does when the iterator for an `into_iter` array field yields
+/// the wrong number of items.
+#[derive(Clone, Copy)]
+enum LenCheck {
+    Panic,
+    /// Return an error converted from a `&'static str`.
+    Error,
+}
+
+/// The name of the constructor parameter of a field, if not the field's own name.
+#[derive(Clone)]
+enum ArgName {
+    Explicit(syn::Ident),
+    /// Derived from the field type, e.g. `user_id` for `UserId`.
+    FromType,
+}
+
+/// How a constructor argument is converted into the field value.
+#[derive(Clone)]
+enum Conversion {
+    Into,
+    IntoGeneric,
+    IntoIter(Option<proc_macro2::TokenStream>),
+    Optional(Option<proc_macro2::TokenStream>),
+    From(proc_macro2::TokenStream),
+    AsRef(Option<proc_macro2::TokenStream>),
+    ToOwned(Option<proc_macro2::TokenStream>),
+    Boxed,
+    Arc,
+    Rc,
+    Cow,
+}
+
+/// Where the value of a field that is not a constructor argument comes from.
+#[derive(Clone)]
+enum Source {
+    Default,
+    Value(proc_macro2::TokenStream),
+}
+
+/// A zero-sized marker field, set without a constructor argument.
+#[derive(Clone, Copy)]
+enum Marker {
+    PhantomData,
+    PhantomPinned,
+    Unit,
+    /// Marked with `#[Demo(phantom)]`, set to its default.
+    Explicit,
+}
+
+impl Marker {
+    /// The marker type named by `ty`, if any. `PhantomData` and `PhantomPinned` are
+    /// recognized unqualified or through `marker`, `core::marker` or `std::marker` only,
+    /// so that types of the same name elsewhere are not mistaken for them.
+    fn from_type(ty: &syn::Type) -> Option<Marker> {
+        match *ty {
+            syn::Type::Tuple(ref tuple) if tuple.elems.is_empty() => Some(Marker::Unit),
+            syn::Type::Paren(ref paren) => Marker::from_type(&paren.elem),
+            syn::Type::Group(ref group) => Marker::from_type(&group.elem),
+            syn::Type::Path(ref path) if path.qself.is_none() => {
+                let segments: Vec<_> = path
+                    .path
+                    .segments
+                    .iter()
+                    .map(|s| s.ident.to_string())
+                    .collect();
+                let (last, prefix) = segments.split_last()?;
+                let prefix: Vec<_> = prefix.iter().map(String::as_str).collect();
+                match prefix[..] {
+                    [] | ["marker"] | ["core", "marker"] | ["std", "marker"] => {}
+                    _ => return None,
+                }
+                match last.as_str() {
+                    "PhantomData" => Some(Marker::PhantomData),
+                    "PhantomPinned" => Some(Marker::PhantomPinned),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn as_init(self, span: proc_macro2::Span) -> proc_macro2::TokenStream {
+        match self {
+            Marker::PhantomData => quote_spanned!(span => ::core::marker::PhantomData),
+            Marker::PhantomPinned => quote_spanned!(span => ::core::marker::PhantomPinned),
+            Marker::Unit => quote_spanned!(span => ()),
+            Marker::Explicit => quote_spanned!(span => ::core::default::Default::default()),
+        }
+    }
+
+    fn doc(self) -> &'static str {
+        match self {
+            Marker::PhantomData => "`PhantomData`",
+            Marker::PhantomPinned => "`PhantomPinned`",
+            Marker::Unit => "`()`",
+            Marker::Explicit => "`Default::default()`",
+        }
+    }
+}
+
+impl Conversion {
+    fn name(&self) -> &'static str {
+        match *self {
+            Conversion::Into => "into",
+            Conversion::IntoGeneric => "into_generic",
+            Conversion::IntoIter(_) => "into_iter",
+            Conversion::Optional(_) => "optional",
+            Conversion::From(_) => "from",
+            Conversion::AsRef(_) => "as_ref",
+            Conversion::ToOwned(_) => "to_owned",
+            Conversion::Boxed => "boxed",
+            Conversion::Arc => "arc",
+            Conversion::Rc => "rc",
+            Conversion::Cow => "cow",
+        }
+    }
+}
+
+impl Source {
+    fn name(&self) -> &'static str {
+        match *self {
+            Source::Default => "default",
+            Source::Value(_) => "value",
+        }
+    }
+}
+
+impl FieldAttr {
+    pub fn is_arg(&self) -> bool {
+        !self.skip && self.source.is_none()
+    }
+
+    pub fn as_tokens(
+        &self,
+        name: &syn::Ident,
+        ty: &syn::Type,
+        span: proc_macro2::Span,
+    ) -> proc_macro2::TokenStream {
+        match self.source {
+            Some(Source::Value(ref s)) => return quote_spanned!(span => #s),
+            Some(Source::Default) => {
+                return quote_spanned!(span => ::core::default::Default::default());
+            }
+            None if self.skip => return quote_spanned!(span => ::core::default::Default::default()),
+            None => {}
+        }
+        match self.conversion {
+            Some(Conversion::Into) | Some(Conversion::IntoGeneric) => {
+                quote_spanned!(span => ::core::convert::Into::into(#name))
+            }
+            Some(Conversion::IntoIter(_)) => {
+                quote_spanned!(span => ::core::iter::Iterator::collect(::core::iter::IntoIterator::into_iter(#name)))
+            }
+            Some(Conversion::Optional(Some(ref s))) => {
+                quote_spanned!(span => ::core::option::Option::unwrap_or_else(#name, || #s))
+            }
+            Some(Conversion::Optional(None)) => {
+                quote_spanned!(span => ::core::option::Option::unwrap_or_default(#name))
+            }
+            Some(Conversion::From(ref s)) => {
+                quote_spanned!(span => <#ty as ::core::convert::From<#s>>::from(#name))
+            }
+            Some(Conversion::AsRef(ref s)) => {
+                let borrowed = borrowed_type(ty, s.as_ref());
+                let alloc = alloc_crate();
+                quote_spanned! {span =>
+                    <#borrowed as #alloc::borrow::ToOwned>::to_owned(
+                        ::core::convert::AsRef::<#borrowed>::as_ref(&#name)
+                    )
+                }
+            }
+            Some(Conversion::ToOwned(ref s)) => {
+                let borrowed = borrowed_type(ty, s.as_ref());
+                let alloc = alloc_crate();
+                quote_spanned!(span => <#borrowed as #alloc::borrow::ToOwned>::to_owned(#name))
+            }
+            Some(ref conversion @ (Conversion::Boxed | Conversion::Arc | Conversion::Rc)) => {
+                let (layers, _) = pointer_layers(ty, conversion);
+                layers.iter().rev().fold(
+                    quote_spanned!(span => #name),
+                    |value, layer| quote_spanned!(span => <#layer>::new(#value)),
+                )
+            }
+            Some(Conversion::Cow) => {
+                let alloc = alloc_crate();
+                quote_spanned!(span => #alloc::borrow::Cow::Borrowed(#name))
+            }
+            None => quote_spanned!(span => #name),
+        }
+    }
+
+    fn set_conversion(&mut self, conversion: Conversion) {
+        if let Some(ref prev) = self.conversion {
+            panic!(
+                "Conflicting #[Demo] attributes: `{}` and `{}`",
+                prev.name(),
+                conversion.name()
+            );
+        }
+        self.conversion = Some(conversion);
+    }
+
+    /// Descriptions of the options of `field` that have no effect.
+    fn warnings(
+        &self,
+        field: &syn::Field,
+        idx: usize,
+        generics: &syn::Generics,
+        options: &DemoOptions,
+    ) -> Vec<String> {
+        let name = match field.ident {
+            Some(ref ident) => ident.to_string(),
+            None => idx.to_string(),
+        };
+        let ty = &field.ty;
+        let mut warnings = Vec::new();
+        if self.phantom.is_none()
+            && let Some(marker) = Marker::from_type(ty)
+        {
+            let has_options = self.conversion.is_some()
+                || self.source.is_some()
+                || self.skip
+                || self.no_into
+                || self.order.is_some()
+                || self.arg.is_some();
+            if has_options {
+                warnings.push(format!(
+                    "#[Demo] options have no effect on field `{}`, which is always set to {}",
+                    name,
+                    marker.doc()
+                ));
+            }
+            return warnings;
+        }
+        match self.conversion {
+            Some(Conversion::Into) if generic_param(ty, generics).is_some() => {
+                let param = generic_param(ty, generics).unwrap();
+                warnings.push(format!(
+                    "#[Demo(into)] on field `{}` of generic type `{}` has no effect \
+                     unless `{}` is given explicitly",
+                    name, param, param
+                ));
+            }
+            Some(ref conversion @ (Conversion::Into | Conversion::IntoGeneric))
+                if options.applies_into_to(ty) =>
+            {
+                warnings.push(format!(
+                    "#[Demo({})] on field `{}` is redundant with the `into` of the type",
+                    conversion.name(),
+                    name
+                ));
+            }
+            Some(Conversion::IntoIter(_)) if !is_collection_type(ty) => {
+                warnings.push(format!(
+                    "#[Demo(into_iter)] on field `{}` of type `{}`, which is not a collection",
+                    name,
+                    quote!(#ty)
+                ));
+            }
+            _ => {}
+        }
+        if self.no_into && !options.applies_into_to(ty) {
+            warnings.push(format!(
+                "#[Demo(no_into)] has no effect on field `{}` without an `into` of the type",
+                name
+            ));
+        }
+        warnings
+    }
+
+    fn set_phantom(&mut self, phantom: bool) {
+        if self.phantom == Some(!phantom) {
+            panic!("Conflicting #[Demo] attributes: `phantom` and `not_phantom`");
+        }
+        self.phantom = Some(phantom);
+    }
+
+    fn set_arg(&mut self, arg: ArgName) {
+        if self.arg.is_some() {
+            panic!("Duplicate #[Demo(arg)] attribute");
+        }
+        self.arg = Some(arg);
+    }
+
+    /// The argument of `value`, `optional = ..` or `from` as written, or else `tokens`.
+    fn written_or(&self, tokens: &proc_macro2::TokenStream) -> String {
+        self.written.clone().unwrap_or_else(|| tokens.to_string())
+    }
+
+    fn set_source(&mut self, source: Source) {
+        if let Some(ref prev) = self.source {
+            panic!(
+                "Conflicting #[Demo] attributes: `{}` and `{}`",
+                prev.name(),
+                source.name()
+            );
+        }
+        self.source = Some(source);
+    }
+
+    pub fn parse(attrs: &[syn::Attribute]) -> FieldAttr {
+        let mut result = FieldAttr::default();
+        for attr in attrs.iter() {
+            match attr.style {
+                syn::AttrStyle::Outer => {}
+                _ => continue,
+            }
+            let last_attr_path = attr
+                .path()
+                .segments
+                .last()
+                .expect("Expected at least one segment where #[segment[::segment*](..)]");
+            if last_attr_path.ident != "Demo" {
+                continue;
+            }
+            match attr.meta {
+                syn::Meta::List(_) => {}
+                _ if attr.path().is_ident("Demo") => {
+                    panic!("Invalid #[Demo] attribute, expected #[Demo(..)]")
+                }
+                _ => continue,
+            }
+            attr.parse_nested_meta(|meta| result.parse_item(meta))
+                .unwrap_or_else(|err| panic!("Invalid #[Demo] attribute: {}", err));
+        }
+        if let Some(ref conversion) = result.conversion {
+            if result.no_into {
+                panic!(
+                    "Conflicting #[Demo] attributes: `no_into` and `{}`",
+                    conversion.name()
+                );
+            }
+            if result.skip {
+                panic!(
+                    "Conflicting #[Demo] attributes: `skip` and `{}`",
+                    conversion.name()
+                );
+            }
+            if let Some(ref source) = result.source {
+                panic!(
+                    "Conflicting #[Demo] attributes: `{}` and `{}`",
+                    source.name(),
+                    conversion.name()
+                );
+            }
+        }
+        if result.override_ {
+            let other = match (&result.source, result.phantom) {
+                (Some(source), _) => Some(source.name()),
+                (None, Some(true)) => Some("phantom"),
+                (None, _) if result.skip => Some("skip"),
+                (None, _) => None,
+            };
+            if let Some(other) = other {
+                panic!("Conflicting #[Demo] attributes: `override` and `{}`", other);
+            }
+        }
+        if result.phantom == Some(true) {
+            let other = match (&result.conversion, &result.source) {
+                (Some(conversion), _) => Some(conversion.name()),
+                (None, Some(source)) => Some(source.name()),
+                (None, None) if result.skip => Some("skip"),
+                (None, None) => None,
+            };
+            if let Some(other) = other {
+                panic!("Conflicting #[Demo] attributes: `phantom` and `{}`", other);
+            }
+        }
+        result
+    }
+
+    fn parse_item(&mut self, meta: syn::meta::ParseNestedMeta) -> syn::Result<()> {
+        let path = &meta.path;
+        if meta.input.peek(Token![=]) {
+            match path.get_ident() {
+                Some(ident) if ident == "into_iter" => {
+                    let ty = parse_type_value(&meta)?;
+                    self.set_conversion(Conversion::IntoIter(Some(ty)));
+                }
+                Some(ident) if ident == "value" => {
+                    let (expr, text) = parse_written_expr_value(&meta)?;
+                    self.set_source(Source::Value(expr));
+                    self.written = Some(text);
+                }
+                Some(ident) if ident == "optional" => {
+                    let (expr, text) = parse_written_expr_value(&meta)?;
+                    self.set_conversion(Conversion::Optional(Some(expr)));
+                    self.written = Some(text);
+                }
+                Some(ident) if ident == "from" => {
+                    let (ty, text) = parse_written_type_value(&meta)?;
+                    self.set_conversion(Conversion::From(ty));
+                    self.written = Some(text);
+                }
+                Some(ident) if ident == "as_ref" => {
+                    let ty = parse_type_value(&meta)?;
+                    self.set_conversion(Conversion::AsRef(Some(ty)));
+                }
+                Some(ident) if ident == "to_owned" => {
+                    let ty = parse_type_value(&meta)?;
+                    self.set_conversion(Conversion::ToOwned(Some(ty)));
+                }
+                Some(ident) if ident == "arg" => {
+                    let input = meta.value()?;
+                    let name = if input.peek(syn::LitStr) {
+                        input.parse::<syn::LitStr>()?.parse()?
+                    } else {
+                        input.parse()?
+                    };
+                    self.set_arg(ArgName::Explicit(name));
+                }
+                Some(ident) if ident == "doc" => {
+                    if self.doc.is_some() {
+                        panic!("Duplicate #[Demo(doc = ..)] attribute");
+                    }
+                    let doc: syn::LitStr = meta.value()?.parse()?;
+                    self.doc = Some(doc.value());
+                }
+                Some(ident) if ident == "len_check" => {
+                    if self.len_check.is_some() {
+                        panic!("Duplicate #[Demo(len_check = ..)] attribute");
+                    }
+                    let value: syn::LitStr = meta.value()?.parse()?;
+                    self.len_check = match value.value().as_str() {
+                        "panic" => Some(LenCheck::Panic),
+                        "error" => Some(LenCheck::Error),
+                        _ => return Err(meta.error("expected `\"panic\"` or `\"error\"`")),
+                    };
+                }
+                Some(ident) if ident == "validate" => {
+                    if self.validate.is_some() {
+                        panic!("Duplicate #[Demo(validate = ..)] attribute");
+                    }
+                    self.validate = Some(parse_expr_value(&meta)?);
+                }
+                Some(ident) if ident == "bound" => {
+                    if self.bound.is_some() {
+                        panic!("Duplicate #[Demo(bound = ..)] attribute");
+                    }
+                    self.bound = Some(parse_bound_value(&meta)?);
+                }
+                Some(ident) if ident == "order" => {
+                    if self.order.is_some() {
+                        panic!("Duplicate #[Demo(order = ..)] attribute");
+                    }
+                    let order: syn::LitInt = meta.value()?.parse()?;
+                    self.order = Some(order.base10_parse()?);
+                }
+                _ => panic!(
+                    "Invalid #[Demo] attribute: #[Demo({} = ..)]",
+                    path_to_string(path)
+                ),
+            }
+            return Ok(());
+        }
+        if meta.input.peek(syn::token::Paren) {
+            panic!(
+                "Invalid #[Demo] attribute: #[Demo({}(..))]",
+                path_to_string(path)
+            );
+        }
+        match path.get_ident() {
+            Some(ident) if ident == "default" => {
+                self.set_source(Source::Default);
+            }
+            Some(ident) if ident == "into" => {
+                self.set_conversion(Conversion::Into);
+            }
+            Some(ident) if ident == "into_iter" => {
+                self.set_conversion(Conversion::IntoIter(None));
+            }
+            Some(ident) if ident == "into_generic" => {
+                self.set_conversion(Conversion::IntoGeneric);
+            }
+            Some(ident) if ident == "optional" => {
+                self.set_conversion(Conversion::Optional(None));
+            }
+            Some(ident) if ident == "as_ref" => {
+                self.set_conversion(Conversion::AsRef(None));
+            }
+            Some(ident) if ident == "to_owned" => {
+                self.set_conversion(Conversion::ToOwned(None));
+            }
+            Some(ident) if ident == "override" => {
+                self.override_ = true;
+            }
+            Some(ident) if ident == "boxed" => {
+                self.set_conversion(Conversion::Boxed);
+            }
+            Some(ident) if ident == "arc" => {
+                self.set_conversion(Conversion::Arc);
+            }
+            Some(ident) if ident == "rc" => {
+                self.set_conversion(Conversion::Rc);
+            }
+            Some(ident) if ident == "cow" => {
+                self.set_conversion(Conversion::Cow);
+            }
+            Some(ident) if ident == "skip" => {
+                if self.skip {
+                    panic!("Duplicate #[Demo(skip)] attribute");
+                }
+                self.skip = true;
+            }
+            Some(ident) if ident == "no_into" => {
+                self.no_into = true;
+            }
+            Some(ident) if ident == "phantom" => {
+                self.set_phantom(true);
+            }
+            Some(ident) if ident == "not_phantom" => {
+                self.set_phantom(false);
+            }
+            Some(ident) if ident == "arg" => {
+                self.set_arg(ArgName::FromType);
+            }
+            _ => panic!(
+                "Invalid #[Demo] attribute: #[Demo({})]",
+                path_to_string(path)
+            ),
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+struct FieldExt<'a> {
+    ty: &'a syn::Type,
+    attr: FieldAttr,
+    /// Name the field's value is bound to in the constructor body.
+    ident: syn::Ident,
+    /// Name of the constructor parameter.
+    arg: syn::Ident,
+    member: syn::Member,
+    /// Description of the field for the constructor docs.
+    doc: Option<String>,
+    /// The `#[cfg]` attributes of the field, repeated on everything generated for it.
+    cfgs: Vec<&'a syn::Attribute>,
+    /// Options of the field that have no effect, reported as warnings.
+    warnings: Vec<String>,
+    /// Span of the field, used for the generated argument and initializer so that
+    /// errors in them point at the field instead of the derive.
+    span: proc_macro2::Span,
+}
+
+impl<'a> FieldExt<'a> {
+    pub fn new(
+        field: &'a syn::Field,
+        idx: usize,
+        named: bool,
+        generics: &syn::Generics,
+        options: &DemoOptions,
+    ) -> FieldExt<'a> {
+        let mut attr = FieldAttr::parse(&field.attrs);
+        let warnings = attr.warnings(field, idx, generics, options);
+        if attr.conversion.is_none() && !attr.no_into && options.applies_into_to(&field.ty) {
+            attr.conversion = Some(Conversion::Into);
+        }
+        if let Some(len_check) = attr.len_check {
+            let array = matches!(attr.conversion, Some(Conversion::IntoIter(_)))
+                && matches!(field.ty, syn::Type::Array(_));
+            if !array {
+                panic!("#[Demo(len_check = ..)] applies to #[Demo(into_iter)] array fields only");
+            }
+            if let (LenCheck::Error, None) = (len_check, &options.error) {
+                panic!("#[Demo(len_check = \"error\")] requires #[Demo(error = ..)]");
+            }
+        }
+        let doc = attr.doc.clone().or_else(|| doc_comment(&field.attrs));
+        let arg = match attr.arg {
+            Some(ArgName::Explicit(ref name)) => name.clone(),
+            Some(ArgName::FromType) => arg_name_from_type(&field.ty),
+            None => match field.ident {
+                Some(ref ident) => ident.clone(),
+                None => syn::Ident::new(&format!("f{}", idx), proc_macro2::Span::call_site()),
+            },
+        };
+        FieldExt {
+            ty: &field.ty,
+            attr,
+            ident: if named {
+                field.ident.clone().unwrap()
+            } else {
+                arg.clone()
+            },
+            arg,
+            doc,
+            cfgs: field
+                .attrs
+                .iter()
+                .filter(|a| a.path().is_ident("cfg"))
+                .collect(),
+            warnings,
+            member: match field.ident {
+                Some(ref ident) => syn::Member::Named(ident.clone()),
+                None => syn::Member::Unnamed(idx.into()),
+            },
+            span: match field.ident {
+                Some(ref ident) => ident.span(),
+                None => syn::spanned::Spanned::span(&field.ty),
+            },
+        }
+    }
+
+    /// The marker the field holds, if it is set without a constructor argument for that.
+    fn marker(&self) -> Option<Marker> {
+        match self.attr.phantom {
+            Some(true) => Some(Marker::Explicit),
+            Some(false) => None,
+            None => Marker::from_type(self.ty),
+        }
+    }
+
+    fn generic_ident(&self) -> syn::Ident {
+        let name = to_upper_camel_case(&syn::ext::IdentExt::unraw(&self.arg).to_string());
+        syn::Ident::new(&format!("Into{}", name), proc_macro2::Span::call_site())
+    }
+
+    /// The where-clause predicates the constructor needs to build the field, unless
+    /// replaced with `#[Demo(bound = "..")]`. Only bounds on types that mention type
+    /// parameters of `generics` are needed, others hold or fail regardless.
+    fn bounds(&self, generics: &syn::Generics) -> Vec<proc_macro2::TokenStream> {
+        if let Some(ref bound) = self.attr.bound {
+            return bound
+                .iter()
+                .map(|predicate| my_quote!(#predicate))
+                .collect();
+        }
+        let ty = self.ty;
+        if !mentions_type_params(&my_quote!(#ty), generics) {
+            return Vec::new();
+        }
+        let default = my_quote!(#ty: ::core::default::Default);
+        match self.marker() {
+            Some(Marker::Explicit) => return vec![default],
+            Some(_) => return Vec::new(),
+            None => {}
+        }
+        match self.attr.source {
+            Some(Source::Default) => return vec![default],
+            Some(Source::Value(_)) => return Vec::new(),
+            None if self.attr.skip => return vec![default],
+            None => {}
+        }
+        match self.attr.conversion {
+            Some(Conversion::IntoIter(_)) if matches!(ty, syn::Type::Array(_)) => Vec::new(),
+            Some(Conversion::IntoIter(ref s)) => {
+                let item = into_iter_item(ty, s.as_ref());
+                vec![my_quote!(#ty: ::core::iter::FromIterator<#item>)]
+            }
+            Some(Conversion::Optional(None)) => vec![default],
+            Some(Conversion::From(ref s)) => vec![my_quote!(#ty: ::core::convert::From<#s>)],
+            Some(Conversion::AsRef(ref s)) | Some(Conversion::ToOwned(ref s)) => {
+                let borrowed = borrowed_type(ty, s.as_ref());
+                let alloc = alloc_crate();
+                vec![my_quote!(#borrowed: #alloc::borrow::ToOwned<Owned = #ty>)]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// The field as it is set without a constructor parameter, for constructors that
+    /// leave out its parameter: to the fallback of `optional`, or to its default.
+    fn without_arg(&self) -> Self {
+        let mut field = self.clone();
+        field.attr.source = Some(match field.attr.conversion.take() {
+            Some(Conversion::Optional(Some(s))) => Source::Value(s),
+            _ => Source::Default,
+        });
+        field
+    }
+
+    /// The predicates of the `#[cfg]` attributes of the field.
+    fn cfg_predicates(&self) -> Vec<proc_macro2::TokenStream> {
+        self.cfgs
+            .iter()
+            .map(|a| match a.parse_args() {
+                Ok(predicate) => predicate,
+                Err(e) => panic!("Invalid #[cfg] attribute: {}", e),
+            })
+            .collect()
+    }
+
+    pub fn as_generic(&self) -> Option<proc_macro2::TokenStream> {
+        let cfgs = &self.cfgs;
+        match self.attr.conversion {
+            Some(Conversion::IntoGeneric) if self.as_arg().is_some() => {
+                let generic = self.generic_ident();
+                let ty = &self.ty;
+                Some(quote_spanned!(self.span => #(#cfgs)* #generic: ::core::convert::Into<#ty>))
             }
+            _ => None,
         }
-        false
     }
 
-    attrs
-        .iter()
-        .filter(|a| is_lint(&a.meta) || is_cfg_attr_lint(&a.meta))
-        .cloned()
-        .collect()
-}
+    pub fn as_arg(&self) -> Option<proc_macro2::TokenStream> {
+        let cfgs = &self.cfgs;
+        self.as_param().map(|param| my_quote!(#(#cfgs)* #param))
+    }
 
-struct DemoOptions {
-    visibility: Option<syn::Visibility>,
-}
+    fn as_param(&self) -> Option<proc_macro2::TokenStream> {
+        if self.marker().is_some() || !self.attr.is_arg() {
+            return None;
+        }
 
-impl DemoOptions {
-    fn from_attributes(attrs: &[Attribute]) -> Self {
-        // Default visibility is public
-        let mut visibility = Some(Visibility::Public(syn::token::Pub {
-            span: proc_macro2::Span::call_site(),
-        }));
+        let ident = self.param_ident();
+        let ty = &self.ty;
 
-        for attr in attrs {
-            if attr.path().is_ident("Demo") {
-                attr.parse_nested_meta(|meta| {
-                    if meta.path.is_ident("visibility") {
-                        let value: Lit = meta.value()?.parse()?;
-                        if let Lit::Str(lit_str) = value {
-                            // Parse the visibility string into a syn::Visibility type
-                            let parsed_visibility: Visibility =
-                                lit_str.parse().expect("Invalid visibility");
-                            visibility = Some(parsed_visibility);
-                        }
-                        Ok(())
-                    } else {
-                        Err(meta.error("unsupported attribute"))
-                    }
-                })
-                .unwrap_or(());
+        match self.attr.conversion {
+            Some(Conversion::Into) => {
+                Some(quote_spanned!(self.span => #ident: impl ::core::convert::Into<#ty>))
+            }
+            Some(Conversion::IntoIter(ref s)) => {
+                let item = into_iter_item(ty, s.as_ref());
+                Some(
+                    quote_spanned!(self.span => #ident: impl ::core::iter::IntoIterator<Item = #item>),
+                )
+            }
+            Some(Conversion::IntoGeneric) => {
+                let generic = self.generic_ident();
+                Some(quote_spanned!(self.span => #ident: #generic))
+            }
+            Some(Conversion::Optional(_)) => {
+                Some(quote_spanned!(self.span => #ident: ::core::option::Option<#ty>))
+            }
+            Some(Conversion::From(ref s)) => Some(quote_spanned!(self.span => #ident: #s)),
+            Some(Conversion::AsRef(ref s)) => {
+                let borrowed = borrowed_type(ty, s.as_ref());
+                Some(quote_spanned!(self.span => #ident: impl ::core::convert::AsRef<#borrowed>))
+            }
+            Some(Conversion::ToOwned(ref s)) => {
+                let borrowed = borrowed_type(ty, s.as_ref());
+                Some(quote_spanned!(self.span => #ident: &#borrowed))
+            }
+            Some(ref conversion @ (Conversion::Boxed | Conversion::Arc | Conversion::Rc)) => {
+                let (_, inner) = pointer_layers(ty, conversion);
+                Some(quote_spanned!(self.span => #ident: #inner))
             }
+            Some(Conversion::Cow) => {
+                let (lifetime, inner) = cow_parts(ty);
+                Some(quote_spanned!(self.span => #ident: &#lifetime #inner))
+            }
+            None => Some(quote_spanned!(self.span => #ident: #ty)),
         }
+    }
 
-        DemoOptions { visibility }
+    pub fn as_init(&self) -> proc_macro2::TokenStream {
+        if let Some(marker) = self.marker() {
+            marker.as_init(self.span)
+        } else if let (Some(Conversion::IntoIter(_)), syn::Type::Array(array), None) =
+            (&self.attr.conversion, self.ty, &self.attr.source)
+        {
+            self.as_array_init(&array.len)
+        } else {
+            self.attr.as_tokens(&self.arg, self.ty, self.span)
+        }
     }
-}
 
-enum FieldAttr {
-    Default,
-    Into,
-    IntoIter(proc_macro2::TokenStream),
-    Value(proc_macro2::TokenStream),
-}
+    /// Fills an `into_iter` array field from the iterator, checking that it yields
+    /// exactly `len` items.
+    fn as_array_init(&self, len: &syn::Expr) -> proc_macro2::TokenStream {
+        let name = &self.arg;
+        let member = &self.member;
+        let message = format!("wrong number of items for field `{}`", quote!(#member));
+        let mismatch = match self.attr.len_check {
+            Some(LenCheck::Error) => quote_spanned! {self.span =>
+                return ::core::result::Result::Err(::core::convert::From::from(#message))
+            },
+            _ => quote_spanned!(self.span => ::core::panic!("{}", #message)),
+        };
+        let iter = syn::Ident::new("iter", proc_macro2::Span::mixed_site());
+        let items = syn::Ident::new("items", proc_macro2::Span::mixed_site());
+        quote_spanned! {self.span => {
+            let mut #iter = ::core::iter::IntoIterator::into_iter(#name);
+            let #items: [::core::option::Option<_>; #len] =
+                ::core::array::from_fn(|_| ::core::iter::Iterator::next(&mut #iter));
+            if #items.iter().any(::core::option::Option::is_none)
+                || ::core::iter::Iterator::next(&mut #iter).is_some()
+            {
+                #mismatch;
+            }
+            #items.map(::core::option::Option::unwrap)
+        }}
+    }
 
-impl FieldAttr {
-    pub fn as_tokens(&self, name: &syn::Ident) -> proc_macro2::TokenStream {
-        match *self {
-            FieldAttr::Default => my_quote!(::core::default::Default::default()),
-            FieldAttr::Into => my_quote!(::core::convert::Into::into(#name)),
-            FieldAttr::IntoIter(_) => {
-                my_quote!(::core::iter::Iterator::collect(::core::iter::IntoIterator::into_iter(#name)))
+    /// The argument to the constructor that builds the field from its value, as returned
+    /// by `into_demo_parts` under the name of the field.
+    fn as_part_arg(&self) -> proc_macro2::TokenStream {
+        let ident = &self.ident;
+        match self.attr.conversion {
+            None
+            | Some(Conversion::Into)
+            | Some(Conversion::IntoGeneric)
+            | Some(Conversion::IntoIter(_))
+            | Some(Conversion::AsRef(_)) => my_quote!(#ident),
+            Some(Conversion::Optional(_)) => my_quote!(::core::option::Option::Some(#ident)),
+            Some(Conversion::ToOwned(_)) => my_quote!(&#ident),
+            Some(Conversion::Boxed) if pointer_layers(self.ty, &Conversion::Boxed).0.len() == 1 => {
+                my_quote!(*#ident)
             }
-            FieldAttr::Value(ref s) => my_quote!(#s),
+            Some(ref conversion) => panic!(
+                "#[Demo(parts)] cannot undo #[Demo({})] on field `{}`",
+                conversion.name(),
+                self.ident
+            ),
         }
     }
 
-    pub fn parse(attrs: &[syn::Attribute]) -> Option<FieldAttr> {
-        let mut result = None;
-        for attr in attrs.iter() {
-            match attr.style {
-                syn::AttrStyle::Outer => {}
-                _ => continue,
+    /// The line describing the constructor parameter of the field.
+    pub fn arg_doc(&self) -> String {
+        let note = match self.attr.conversion {
+            None => None,
+            Some(Conversion::Into) | Some(Conversion::IntoGeneric) => {
+                Some("Converted with `Into`.".to_owned())
             }
-            let last_attr_path = attr
-                .path()
-                .segments
-                .last()
-                .expect("Expected at least one segment where #[segment[::segment*](..)]");
-            if last_attr_path.ident != "Demo" {
-                continue;
+            Some(Conversion::IntoIter(_)) => match (self.ty, self.attr.len_check) {
+                (syn::Type::Array(_), Some(LenCheck::Error)) => Some(
+                    "Collected from an iterator, which has to yield exactly as many items as \
+                     the array holds."
+                        .to_owned(),
+                ),
+                (syn::Type::Array(_), _) => Some(
+                    "Collected from an iterator; panics unless it yields exactly as many items \
+                     as the array holds."
+                        .to_owned(),
+                ),
+                _ => Some("Collected from an iterator.".to_owned()),
+            },
+            Some(Conversion::Optional(None)) => {
+                Some("`None` stands for `Default::default()`.".to_owned())
             }
-            let list = match attr.meta {
-                syn::Meta::List(ref l) => l,
-                _ if attr.path().is_ident("Demo") => {
-                    panic!("Invalid #[Demo] attribute, expected #[Demo(..)]")
-                }
-                _ => continue,
-            };
-            if result.is_some() {
-                panic!("Expected at most one #[Demo] attribute");
+            Some(Conversion::Optional(Some(ref s))) => {
+                Some(format!("`None` stands for `{}`.", self.attr.written_or(s)))
             }
-            for item in list
-                .parse_args_with(Punctuated::<syn::Meta, Token![,]>::parse_terminated)
-                .unwrap_or_else(|err| panic!("Invalid #[Demo] attribute: {}", err))
-            {
-                match item {
-                    syn::Meta::Path(path) => match path.get_ident() {
-                        Some(ident) if ident == "default" => {
-                            result = Some(FieldAttr::Default);
-                        }
-                        Some(ident) if ident == "into" => {
-                            result = Some(FieldAttr::Into);
-                        }
-                        _ => panic!(
-                            "Invalid #[Demo] attribute: #[Demo({})]",
-                            path_to_string(&path)
-                        ),
-                    },
-                    syn::Meta::NameValue(kv) => {
-                        if let syn::Expr::Lit(syn::ExprLit {
-                            lit: syn::Lit::Str(ref s),
-                            ..
-                        }) = kv.value
-                        {
-                            let tokens = lit_str_to_token_stream(s)
-                                .unwrap_or_else(|_| panic!("Invalid expression in #[Demo]: `{}`", s.value()));
-
-                            match kv.path.get_ident() {
-                                Some(ident) if ident == "into_iter" => {
-                                    result = Some(FieldAttr::IntoIter(tokens));
-                                }
-                                Some(ident) if ident == "value" => {
-                                    result = Some(FieldAttr::Value(tokens));
-                                }
-                                _ => panic!(
-                                    "Invalid #[Demo] attribute: #[Demo({} = ..)]",
-                                    path_to_string(&kv.path)
-                                ),
-                            }
-                        } else {
-                            panic!("Non-string literal value in #[Demo] attribute");
-                        }
-                    }
-                    syn::Meta::List(l) => {
-                        panic!(
-                            "Invalid #[Demo] attribute: #[Demo({}(..))]",
-                            path_to_string(&l.path)
-                        );
-                    }
-                }
+            Some(Conversion::From(ref s)) => Some(format!(
+                "Converted from `{}` with `From`.",
+                self.attr.written_or(s)
+            )),
+            Some(Conversion::AsRef(_)) | Some(Conversion::ToOwned(_)) => {
+                Some("Stored as an owned copy.".to_owned())
+            }
+            Some(ref conversion @ (Conversion::Boxed | Conversion::Arc | Conversion::Rc)) => {
+                let (layers, _) = pointer_layers(self.ty, conversion);
+                let names: Vec<_> = layers
+                    .iter()
+                    .filter_map(|layer| last_path_segment(layer))
+                    .map(|segment| format!("`{}`", segment.ident))
+                    .collect();
+                Some(format!("Wrapped in {}.", names.join(" and ")))
+            }
+            Some(Conversion::Cow) => Some("Borrowed by the `Cow`.".to_owned()),
+        };
+        let text: Vec<_> = self.doc.iter().cloned().chain(note).collect();
+        if text.is_empty() {
+            format!("* `{}`", self.arg)
+        } else {
+            format!("* `{}` - {}", self.arg, text.join(" "))
+        }
+    }
+
+    /// The line describing how a field that is not a constructor parameter is set.
+    pub fn init_doc(&self) -> String {
+        let member = &self.member;
+        let value = if let Some(marker) = self.marker() {
+            marker.doc().to_owned()
+        } else {
+            match self.attr.source {
+                Some(Source::Value(ref s)) => format!("`{}`", self.attr.written_or(s)),
+                _ => "`Default::default()`".to_owned(),
             }
+        };
+        format!("* `{}` is set to {}.", quote!(#member), value)
+    }
+
+    /// The call of the `validate` hook of the field, made once the field is bound. It
+    /// returns `Result<(), E>` in fallible constructors, and the error is passed on.
+    fn as_validation(&self, fallible: bool) -> Option<proc_macro2::TokenStream> {
+        let validate = self.attr.validate.as_ref()?;
+        let cfgs = &self.cfgs;
+        let ident = &self.ident;
+        let question = if fallible { my_quote!(?) } else { my_quote!() };
+        Some(quote_spanned!(self.span => #(#cfgs)* #validate(&#ident)#question;))
+    }
+
+    /// The `let` statements binding the parameter of the field, if any, under its name and
+    /// the value of the field under the name of the field.
+    pub fn as_let(&self) -> Option<proc_macro2::TokenStream> {
+        let cfgs = &self.cfgs;
+        let arg = self.as_arg().map(|_| {
+            let arg = &self.arg;
+            let param = self.param_ident();
+            quote_spanned!(self.span => #(#cfgs)* let #arg = #param;)
+        });
+        if arg.is_some() && self.attr.conversion.is_none() && self.arg == self.ident {
+            return arg;
         }
-        result
+        let f_name = &self.ident;
+        let ty = &self.ty;
+        let init = self.as_init();
+        Some(quote_spanned!(self.span => #arg #(#cfgs)* let #f_name: #ty = #init;))
+    }
+
+    /// The name of the constructor parameter in the generated code, hidden from the
+    /// expressions of `value` and `optional` by its `mixed_site` span. `as_let` binds it
+    /// under the name of the parameter in declaration order, so that a field referring
+    /// to a parameter of a later field is an error.
+    fn param_ident(&self) -> syn::Ident {
+        let mut ident = self.arg.clone();
+        ident.set_span(proc_macro2::Span::mixed_site());
+        ident
     }
 }
 
-struct FieldExt<'a> {
-    ty: &'a syn::Type,
-    attr: Option<FieldAttr>,
-    ident: syn::Ident,
-    named: bool,
+/// Generated rustdoc of a constructor: a summary, the parameters with the docs of their
+/// fields, the fields that are set without a parameter, and an example call.
+fn constructor_doc<'a>(
+    summary: String,
+    path: &str,
+    args: &[&FieldExt],
+    fields: &[FieldExt],
+    fallible: bool,
+    append: impl Iterator<Item = &'a str>,
+) -> String {
+    let mut sections = vec![summary];
+    if !args.is_empty() {
+        let lines: Vec<_> = args.iter().map(|f| f.arg_doc()).collect();
+        sections.push(format!("# Arguments\n\n{}", lines.join("\n")));
+    }
+    let others: Vec<_> = fields
+        .iter()
+        .filter(|f| f.as_arg().is_none())
+        .map(|f| f.init_doc())
+        .collect();
+    if !others.is_empty() {
+        sections.push(format!("# Fields\n\n{}", others.join("\n")));
+    }
+    let names: Vec<_> = args.iter().map(|f| f.arg.to_string()).collect();
+    sections.push(format!(
+        "# Example\n\n```ignore\nlet value = {}({}){};\n```",
+        path,
+        names.join(", "),
+        if fallible { "?" } else { "" }
+    ));
+    sections.extend(append.map(str::to_owned));
+    sections.join("\n\n")
 }
 
-impl<'a> FieldExt<'a> {
-    pub fn new(field: &'a syn::Field, idx: usize, named: bool) -> FieldExt<'a> {
-        FieldExt {
-            ty: &field.ty,
-            attr: FieldAttr::parse(&field.attrs),
-            ident: if named {
-                field.ident.clone().unwrap()
-            } else {
-                syn::Ident::new(&format!("f{}", idx), proc_macro2::Span::call_site())
+/// The first paragraph of the doc comments in `attrs`, joined into a single line.
+fn doc_comment(attrs: &[syn::Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter_map(|attr| match attr.meta {
+            syn::Meta::NameValue(ref kv) if kv.path.is_ident("doc") => match kv.value {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(ref s),
+                    ..
+                }) => Some(s.value()),
+                _ => None,
             },
-            named,
+            _ => None,
+        })
+        .flat_map(|doc| {
+            doc.split('\n')
+                .map(|l| l.trim().to_owned())
+                .collect::<Vec<_>>()
+        })
+        .skip_while(|l| l.is_empty())
+        .take_while(|l| !l.is_empty())
+        .collect();
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join(" "))
+    }
+}
+
+/// The fields that are constructor arguments, in the order of the explicit `args` list,
+/// of their `order` keys, or else in declaration order.
+fn ordered_args<'a, 'b>(
+    fields: &'b [FieldExt<'a>],
+    arg_list: Option<&[syn::Member]>,
+) -> Vec<&'b FieldExt<'a>> {
+    let args: Vec<_> = fields.iter().filter(|f| f.as_arg().is_some()).collect();
+    let keyed = args.iter().filter(|f| f.attr.order.is_some()).count();
+    if let Some(arg_list) = arg_list {
+        if keyed > 0 {
+            panic!("#[Demo(order = ..)] cannot be combined with #[Demo(args = ..)]");
+        }
+        let mut ordered = Vec::new();
+        for member in arg_list {
+            let field = fields
+                .iter()
+                .find(|f| f.member == *member)
+                .unwrap_or_else(|| {
+                    panic!(
+                        "#[Demo(args = ..)] lists unknown field `{}`",
+                        quote!(#member)
+                    )
+                });
+            if field.as_arg().is_none() {
+                panic!(
+                    "#[Demo(args = ..)] lists `{}`, which is not a constructor argument",
+                    quote!(#member)
+                );
+            }
+            if ordered.iter().any(|f: &&FieldExt| f.member == *member) {
+                panic!(
+                    "#[Demo(args = ..)] lists `{}` more than once",
+                    quote!(#member)
+                );
+            }
+            ordered.push(field);
         }
+        if let Some(missing) = args.iter().find(|f| !arg_list.contains(&f.member)) {
+            let member = &missing.member;
+            panic!(
+                "#[Demo(args = ..)] does not list argument `{}`",
+                quote!(#member)
+            );
+        }
+        return ordered;
+    }
+    if keyed == 0 {
+        return args;
     }
+    if let Some(missing) = args.iter().find(|f| f.attr.order.is_none()) {
+        let member = &missing.member;
+        panic!(
+            "Argument `{}` is missing #[Demo(order = ..)]",
+            quote!(#member)
+        );
+    }
+    let mut ordered = args;
+    ordered.sort_by_key(|f| f.attr.order);
+    if let Some(pair) = ordered
+        .windows(2)
+        .find(|pair| pair[0].attr.order == pair[1].attr.order)
+    {
+        let (first, second) = (&pair[0].member, &pair[1].member);
+        panic!(
+            "Arguments `{}` and `{}` have the same #[Demo(order = ..)]",
+            quote!(#first),
+            quote!(#second)
+        );
+    }
+    ordered
+}
 
-    pub fn is_phantom_data(&self) -> bool {
-        match *self.ty {
-            syn::Type::Path(syn::TypePath {
-                qself: None,
-                ref path,
-            }) => path
-                .segments
-                .last()
-                .map(|x| x.ident == "PhantomData")
-                .unwrap_or(false),
-            _ => false,
+/// Every parameter name has to be unique, and must not be the name another field is
+/// bound to in the constructor body.
+fn check_arg_names(fields: &[FieldExt]) {
+    for (i, field) in fields.iter().enumerate() {
+        if field.as_arg().is_none() {
+            continue;
+        }
+        let clash = fields.iter().enumerate().find(|&(j, other)| {
+            j != i
+                && (other.ident == field.arg
+                    || (other.as_arg().is_some() && other.arg == field.arg))
+        });
+        if let Some((_, other)) = clash {
+            let (member, other) = (&field.member, &other.member);
+            panic!(
+                "Parameter name `{}` of field `{}` clashes with field `{}`",
+                field.arg,
+                quote!(#member),
+                quote!(#other)
+            );
         }
     }
+}
 
-    pub fn as_arg(&self) -> Option<proc_macro2::TokenStream> {
-        if self.is_phantom_data() {
-            return None;
+/// Whether `ts` mentions any of the type parameters of `generics`.
+fn mentions_type_params(ts: &proc_macro2::TokenStream, generics: &syn::Generics) -> bool {
+    ts.clone().into_iter().any(|tt| match tt {
+        proc_macro2::TokenTree::Group(group) => mentions_type_params(&group.stream(), generics),
+        proc_macro2::TokenTree::Ident(ident) => {
+            generics.type_params().any(|param| param.ident == ident)
         }
+        _ => false,
+    })
+}
 
-        let ident = &self.ident;
-        let ty = &self.ty;
+/// Parameter name for `#[Demo(arg)]`, the snake case name of the field type. Keywords,
+/// as from `Box<T>`, become raw identifiers.
+fn arg_name_from_type(ty: &syn::Type) -> syn::Ident {
+    match last_path_segment(ty) {
+        Some(segment) => {
+            let name = to_snake_case(&segment.ident.to_string());
+            let span = proc_macro2::Span::call_site();
+            match syn::parse_str::<syn::Ident>(&name) {
+                Ok(_) => syn::Ident::new(&name, span),
+                Err(_) if !["self", "super", "crate", "_"].contains(&name.as_str()) => {
+                    syn::Ident::new_raw(&name, span)
+                }
+                Err(_) => panic!(
+                    "Cannot use `{}` as the parameter name for `{}`, use #[Demo(arg = \"name\")]",
+                    name,
+                    quote!(#ty)
+                ),
+            }
+        }
+        None => panic!(
+            "Cannot derive a parameter name from `{}`, use #[Demo(arg = \"name\")]",
+            quote!(#ty)
+        ),
+    }
+}
+
+fn last_path_segment(ty: &syn::Type) -> Option<&syn::PathSegment> {
+    match *ty {
+        syn::Type::Path(syn::TypePath {
+            qself: None,
+            ref path,
+        }) => path.segments.last(),
+        _ => None,
+    }
+}
+
+fn generic_type_args(segment: &syn::PathSegment) -> Vec<&syn::Type> {
+    match segment.arguments {
+        syn::PathArguments::AngleBracketed(ref args) => args
+            .args
+            .iter()
+            .filter_map(|arg| match *arg {
+                syn::GenericArgument::Type(ref ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// The types wrapping the value of a field with `#[Demo(boxed)]`, `#[Demo(arc)]` or
+/// `#[Demo(rc)]`, from the pointer inwards, and the wrapped type the constructor takes.
+/// Interior mutability types inside the pointer, as in `Arc<Mutex<T>>`, are included.
+fn pointer_layers<'a>(
+    ty: &'a syn::Type,
+    conversion: &Conversion,
+) -> (Vec<&'a syn::Type>, &'a syn::Type) {
+    let pointer = match *conversion {
+        Conversion::Boxed => "Box",
+        Conversion::Arc => "Arc",
+        _ => "Rc",
+    };
+    let inner = match last_path_segment(ty) {
+        Some(segment) if segment.ident == pointer => match generic_type_args(segment)[..] {
+            [inner] => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    };
+    let mut inner = inner.unwrap_or_else(|| {
+        panic!(
+            "#[Demo({})] expects a field of type `{}<T>`, found `{}`",
+            conversion.name(),
+            pointer,
+            quote!(#ty)
+        )
+    });
+    let mut layers = vec![ty];
+    while let Some(segment) = last_path_segment(inner)
+        && matches!(
+            segment.ident.to_string().as_str(),
+            "Mutex" | "RwLock" | "RefCell" | "Cell"
+        )
+        && let [next] = generic_type_args(segment)[..]
+    {
+        layers.push(inner);
+        inner = next;
+    }
+    (layers, inner)
+}
+
+/// The lifetime and borrowed type of a `Cow<'a, B>` field with `#[Demo(cow)]`.
+fn cow_parts(ty: &syn::Type) -> (&syn::Lifetime, &syn::Type) {
+    if let Some(segment) = last_path_segment(ty)
+        && segment.ident == "Cow"
+        && let syn::PathArguments::AngleBracketed(ref args) = segment.arguments
+        && let [
+            syn::GenericArgument::Lifetime(lifetime),
+            syn::GenericArgument::Type(inner),
+        ] = args.args.iter().collect::<Vec<_>>()[..]
+    {
+        return (lifetime, inner);
+    }
+    panic!(
+        "#[Demo(cow)] expects a field of type `Cow<'a, B>`, found `{}`",
+        quote!(#ty)
+    );
+}
+
+/// Path to the crate providing `ToOwned`, `Box` and friends in the generated code.
+fn alloc_crate() -> proc_macro2::TokenStream {
+    if cfg!(feature = "std") {
+        my_quote!(::std)
+    } else {
+        my_quote!(::alloc)
+    }
+}
 
-        match self.attr {
-            Some(FieldAttr::Default) => None,
-            Some(FieldAttr::Into) => Some(my_quote!(#ident: impl ::core::convert::Into<#ty>)),
-            Some(FieldAttr::IntoIter(ref s)) => {
-                Some(my_quote!(#ident: impl ::core::iter::IntoIterator<Item = #s>))
+/// The borrowed form of an owned field type, e.g. `str` for `String`. Types that are
+/// not recognised borrow as themselves, which works for any `Clone` type.
+fn borrowed_type(
+    ty: &syn::Type,
+    explicit: Option<&proc_macro2::TokenStream>,
+) -> proc_macro2::TokenStream {
+    if let Some(explicit) = explicit {
+        return explicit.clone();
+    }
+    if let Some(segment) = last_path_segment(ty) {
+        match segment.ident.to_string().as_str() {
+            "String" => return my_quote!(str),
+            "PathBuf" => return my_quote!(::std::path::Path),
+            "OsString" => return my_quote!(::std::ffi::OsStr),
+            "CString" => return my_quote!(::core::ffi::CStr),
+            "Vec" => {
+                if let [item] = generic_type_args(segment)[..] {
+                    return my_quote!([#item]);
+                }
             }
-            Some(FieldAttr::Value(_)) => None,
-            None => Some(my_quote!(#ident: #ty)),
+            _ => {}
         }
     }
+    my_quote!(#ty)
+}
 
-    pub fn as_init(&self) -> proc_macro2::TokenStream {
-        let f_name = &self.ident;
-        let init = if self.is_phantom_data() {
-            my_quote!(::core::marker::PhantomData)
-        } else {
-            match self.attr {
-                None => my_quote!(#f_name),
-                Some(ref attr) => attr.as_tokens(f_name),
+/// The type parameter of `generics` that `ty` is, if it is a bare one.
+fn generic_param<'a>(ty: &syn::Type, generics: &'a syn::Generics) -> Option<&'a syn::Ident> {
+    let ident = match *ty {
+        syn::Type::Path(ref path) if path.qself.is_none() => path.path.get_ident()?,
+        _ => return None,
+    };
+    generics
+        .type_params()
+        .map(|param| &param.ident)
+        .find(|param| *param == ident)
+}
+
+/// Whether `ty` may be a collection, i.e. is not a primitive, reference, tuple or pointer.
+fn is_collection_type(ty: &syn::Type) -> bool {
+    const PRIMITIVES: &[&str] = &[
+        "bool", "char", "str", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32",
+        "i64", "i128", "isize", "f32", "f64",
+    ];
+    match *ty {
+        syn::Type::Path(ref path) => match path.path.get_ident() {
+            Some(ident) => !PRIMITIVES.iter().any(|p| ident == p),
+            None => true,
+        },
+        syn::Type::Paren(ref paren) => is_collection_type(&paren.elem),
+        syn::Type::Group(ref group) => is_collection_type(&group.elem),
+        syn::Type::Reference(_)
+        | syn::Type::Tuple(_)
+        | syn::Type::Ptr(_)
+        | syn::Type::BareFn(_)
+        | syn::Type::Never(_) => false,
+        _ => true,
+    }
+}
+
+/// The item type of a collection field used with `#[Demo(into_iter)]`, inferred from the
+/// generic arguments of well-known collection types unless given explicitly.
+fn into_iter_item(
+    ty: &syn::Type,
+    explicit: Option<&proc_macro2::TokenStream>,
+) -> proc_macro2::TokenStream {
+    if let Some(explicit) = explicit {
+        return explicit.clone();
+    }
+    if let syn::Type::Array(ref array) = *ty {
+        let item = &array.elem;
+        return my_quote!(#item);
+    }
+    if let Some(segment) = last_path_segment(ty) {
+        let args = generic_type_args(segment);
+        match (segment.ident.to_string().as_str(), &args[..]) {
+            (
+                "Vec" | "VecDeque" | "LinkedList" | "HashSet" | "BTreeSet" | "BinaryHeap",
+                [item, ..],
+            ) => return my_quote!(#item),
+            ("HashMap" | "BTreeMap", [key, value, ..]) => return my_quote!((#key, #value)),
+            ("Box" | "Rc" | "Arc", [syn::Type::Slice(slice)]) => {
+                let item = &slice.elem;
+                return my_quote!(#item);
             }
+            _ => {}
+        }
+    }
+    panic!(
+        "Cannot infer the item type of `{}` for #[Demo(into_iter)], use #[Demo(into_iter = \"T\")]",
+        quote!(#ty)
+    );
+}
+
+/// Parses the value of an expression option, either written directly
+/// (`value = vec![1]`) or wrapped in a string literal (`value = "vec![1]"`).
+fn parse_expr_value(meta: &syn::meta::ParseNestedMeta) -> syn::Result<TokenStream2> {
+    parse_written_expr_value(meta).map(|(expr, _)| expr)
+}
+
+/// Like `parse_expr_value`, together with the expression as written, for the docs.
+fn parse_written_expr_value(
+    meta: &syn::meta::ParseNestedMeta,
+) -> syn::Result<(TokenStream2, String)> {
+    let expr: syn::Expr = meta.value()?.parse()?;
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(ref s),
+            ..
+        }) => Ok((
+            lit_str_to_token_stream(s)
+                .unwrap_or_else(|_| panic!("Invalid expression in #[Demo]: `{}`", s.value())),
+            s.value(),
+        )),
+        expr => {
+            let expr = my_quote!(#expr);
+            let text = written_text(&expr);
+            Ok((expr, text))
+        }
+    }
+}
+
+/// Parses a comma-separated list of fields such as `args = "b, a"` or `args = "1, 0"`.
+fn parse_args_value(meta: &syn::meta::ParseNestedMeta) -> syn::Result<Vec<syn::Member>> {
+    let value: syn::LitStr = meta.value()?.parse()?;
+    let members = value.parse_with(Punctuated::<syn::Member, Token![,]>::parse_terminated)?;
+    Ok(members.into_iter().collect())
+}
+
+/// Parses a comma-separated list of where-clause predicates such as
+/// `bound = "T: Clone, U: Default"`, which may be empty.
+fn parse_bound_value(meta: &syn::meta::ParseNestedMeta) -> syn::Result<Vec<syn::WherePredicate>> {
+    let value: syn::LitStr = meta.value()?.parse()?;
+    let predicates =
+        value.parse_with(Punctuated::<syn::WherePredicate, Token![,]>::parse_terminated)?;
+    Ok(predicates.into_iter().collect())
+}
+
+/// Parses the value of a type option, either written directly (`into_iter = bool`)
+/// or wrapped in a string literal (`into_iter = "bool"`).
+fn parse_type_value(meta: &syn::meta::ParseNestedMeta) -> syn::Result<TokenStream2> {
+    parse_written_type_value(meta).map(|(ty, _)| ty)
+}
+
+/// Like `parse_type_value`, together with the type as written, for the docs.
+fn parse_written_type_value(
+    meta: &syn::meta::ParseNestedMeta,
+) -> syn::Result<(TokenStream2, String)> {
+    let input = meta.value()?;
+    if input.peek(syn::LitStr) {
+        let s: syn::LitStr = input.parse()?;
+        let ty = lit_str_to_token_stream(&s)
+            .unwrap_or_else(|_| panic!("Invalid type in #[Demo]: `{}`", s.value()));
+        Ok((ty, s.value()))
+    } else {
+        let ty: syn::Type = input.parse()?;
+        let ty = my_quote!(#ty);
+        let text = written_text(&ty);
+        Ok((ty, text))
+    }
+}
+
+/// The source text of `tokens`, with a space where the source has whitespace between
+/// them, or their `Display` form for tokens that do not come from the source as is.
+fn written_text(tokens: &TokenStream2) -> String {
+    let mut text = String::new();
+    let mut end = None;
+    for tt in tokens.clone() {
+        let span = tt.span();
+        // Line 0 means that the compiler does not provide the locations.
+        let (Some(source), true) = (span.source_text(), span.start().line > 0) else {
+            return tokens.to_string();
         };
-        if self.named {
-            my_quote!(#f_name: #init)
-        } else {
-            my_quote!(#init)
+        if end.is_some_and(|end| end != span.start()) {
+            text.push(' ');
         }
+        text.push_str(&source);
+        end = Some(span.end());
     }
+    text
 }
 
 fn lit_str_to_token_stream(s: &syn::LitStr) -> Result<TokenStream2, proc_macro2::LexError> {
     let code = s.value();
     let ts: TokenStream2 = code.parse()?;
-    Ok(set_ts_span_recursive(ts, &s.span()))
+    let literal = s.token();
+    let repr = literal.to_string();
+    // The contents start right after the opening quote, i.e. at 1 for `"..."` and at 3
+    // for `r#"..."#`. Escape sequences shift the source offsets away from the offsets in
+    // the value, so sub-spans are only mapped for literals written verbatim.
+    match repr.find('"').map(|quote| quote + 1) {
+        Some(start) if repr.get(start..start + code.len()) == Some(code.as_str()) => {
+            let span_of = |range: Option<Range<usize>>| {
+                range
+                    .and_then(|range| literal.subspan(start + range.start..start + range.end))
+                    .unwrap_or_else(|| s.span())
+            };
+            Ok(set_ts_subspans(ts, &code, &mut 0, &span_of))
+        }
+        _ => Ok(set_ts_span_recursive(ts, &s.span())),
+    }
 }
 
 fn set_ts_span_recursive(ts: TokenStream2, span: &proc_macro2::Span) -> TokenStream2 {
     ts.into_iter()
         .map(|mut tt| {
-            tt.set_span(*span);
             if let proc_macro2::TokenTree::Group(group) = &mut tt {
                 let stream = set_ts_span_recursive(group.stream(), span);
                 *group = proc_macro2::Group::new(group.delimiter(), stream);
             }
+            tt.set_span(*span);
+            tt
+        })
+        .collect()
+}
+
+/// Gives every token the span of its own text within `code`, which is searched from
+/// `cursor` onwards in token order. Tokens that cannot be located, and all tokens on
+/// compilers without support for sub-spans, get the span of the whole literal.
+fn set_ts_subspans(
+    ts: TokenStream2,
+    code: &str,
+    cursor: &mut usize,
+    span_of: &dyn Fn(Option<Range<usize>>) -> proc_macro2::Span,
+) -> TokenStream2 {
+    fn find(code: &str, cursor: &mut usize, text: &str) -> Option<Range<usize>> {
+        let start = *cursor + code.get(*cursor..)?.find(text)?;
+        *cursor = start + text.len();
+        Some(start..*cursor)
+    }
+
+    ts.into_iter()
+        .map(|mut tt| {
+            let range = if let proc_macro2::TokenTree::Group(group) = &mut tt {
+                let (open, close) = match group.delimiter() {
+                    proc_macro2::Delimiter::Parenthesis => ("(", ")"),
+                    proc_macro2::Delimiter::Brace => ("{", "}"),
+                    proc_macro2::Delimiter::Bracket => ("[", "]"),
+                    proc_macro2::Delimiter::None => ("", ""),
+                };
+                let open = find(code, cursor, open);
+                let stream = set_ts_subspans(group.stream(), code, cursor, span_of);
+                *group = proc_macro2::Group::new(group.delimiter(), stream);
+                let close = find(code, cursor, close);
+                open.zip(close).map(|(open, close)| open.start..close.end)
+            } else {
+                find(code, cursor, &tt.to_string())
+            };
+            tt.set_span(span_of(range));
             tt
         })
         .collect()
@@ -563,30 +3561,102 @@ fn to_snake_case(s: &str) -> String {
         s.chars()
             .fold((None, None, String::new()), |(prev, ch, mut acc), next| {
                 if let Some(ch) = ch {
-                    if let Some(prev) = prev {
-                        if ch.is_uppercase()
-                            && (prev.is_lowercase()
-                                || prev.is_numeric()
-                                || (prev.is_uppercase() && next.is_lowercase()))
-                        {
-                            acc.push('_');
-                        }
+                    if let Some(prev) = prev
+                        && ch.is_uppercase()
+                        && (prev.is_lowercase()
+                            || prev.is_numeric()
+                            || (prev.is_uppercase() && next.is_lowercase()))
+                    {
+                        acc.push('_');
                     }
                     acc.extend(ch.to_lowercase());
                 }
                 (ch, Some(next), acc)
             });
     if let Some(next) = next {
-        if let Some(ch) = ch {
-            if (ch.is_lowercase() || ch.is_numeric()) && next.is_uppercase() {
-                acc.push('_');
-            }
+        if let Some(ch) = ch
+            && (ch.is_lowercase() || ch.is_numeric())
+            && next.is_uppercase()
+        {
+            acc.push('_');
         }
         acc.extend(next.to_lowercase());
     }
     acc
 }
 
+fn to_upper_camel_case(s: &str) -> String {
+    s.split('_')
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .into_iter()
+                .flat_map(char::to_uppercase)
+                .chain(chars)
+        })
+        .collect()
+}
+
+#[test]
+fn test_lit_str_to_token_stream() {
+    for code in [
+        r#""vec![-42, 42]""#,
+        r#""\"Thud\".to_owned()""#,
+        r##"r#""Thud".to_owned()"#"##,
+        r#""{ let x = (1, [2]); x.0 }""#,
+    ] {
+        let lit: syn::LitStr = syn::parse_str(code).unwrap();
+        let expected: TokenStream2 = lit.value().parse().unwrap();
+        let actual = lit_str_to_token_stream(&lit).unwrap();
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+}
+
+#[test]
+fn test_constructor_docs() {
+    let ast: syn::DeriveInput = syn::parse_str(
+        r#"
+        struct Foo {
+            /// The count.
+            #[Demo(optional = i64::MAX)]
+            count: i64,
+            #[Demo(from = "Vec<u8>")]
+            bytes: Box<[u8]>,
+            #[Demo(value = vec![1, 2])]
+            list: Vec<u8>,
+            #[Demo(value = "a.len()  * 2")]
+            len: usize,
+        }
+        "#,
+    )
+    .unwrap();
+    let syn::Data::Struct(ref data) = ast.data else {
+        unreachable!()
+    };
+    let options = DemoOptions::from_attributes(&ast.attrs);
+    let output = demo_for_struct(&ast, &data.fields, None, &options).to_string();
+    for line in [
+        "* `count` - The count. `None` stands for `i64::MAX`.",
+        "* `bytes` - Converted from `Vec<u8>` with `From`.",
+        "* `list` is set to `vec![1, 2]`.",
+        "* `len` is set to `a.len()  * 2`.",
+        "let value = Foo::demo(count, bytes);",
+    ] {
+        assert!(output.contains(line), "missing `{}` in {}", line, output);
+    }
+}
+
+#[test]
+fn test_to_upper_camel_case() {
+    assert_eq!(to_upper_camel_case(""), "");
+    assert_eq!(to_upper_camel_case("a"), "A");
+    assert_eq!(to_upper_camel_case("f0"), "F0");
+    assert_eq!(to_upper_camel_case("_hello"), "Hello");
+    assert_eq!(to_upper_camel_case("user_id"), "UserId");
+    assert_eq!(to_upper_camel_case("keepCase"), "KeepCase");
+}
+
 #[test]
 fn test_to_snake_case() {
     assert_eq!(to_snake_case(""), "");
@@ -605,3 +3675,18 @@ fn test_to_snake_case() {
     assert_eq!(to_snake_case("Keep_underscore"), "keep_underscore");
     assert_eq!(to_snake_case("ThisISNotADrill"), "this_is_not_a_drill");
 }
+
+#[test]
+fn test_marker_from_type() {
+    let marker = |ty: &str| match Marker::from_type(&syn::parse_str(ty).unwrap()) {
+        Some(marker) => marker.doc(),
+        None => "",
+    };
+    assert_eq!(marker("PhantomData<T>"), "`PhantomData`");
+    assert_eq!(marker("::core::marker::PhantomData<T>"), "`PhantomData`");
+    assert_eq!(marker("std::marker::PhantomPinned"), "`PhantomPinned`");
+    assert_eq!(marker("()"), "`()`");
+    assert_eq!(marker("my::PhantomData<T>"), "");
+    assert_eq!(marker("Marker<T>"), "");
+    assert_eq!(marker("(u8,)"), "");
+}
@@ -199,6 +199,122 @@ fn test_struct_with_into() {
     );
 }
 
+#[cfg(feature = "std")]
+#[test]
+fn test_struct_with_container_into() {
+    #[derive(Demo, PartialEq, Debug)]
+    #[Demo(into)]
+    pub struct Foo {
+        pub x: String,
+        pub y: i64,
+        #[Demo(no_into)]
+        pub z: Vec<u8>,
+        #[Demo(default)]
+        pub w: u8,
+    }
+
+    assert_eq!(
+        Foo::demo("bar", 1i32, vec![1]),
+        Foo {
+            x: "bar".to_owned(),
+            y: 1,
+            z: vec![1],
+            w: 0,
+        }
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_struct_with_container_into_for() {
+    use std::path::PathBuf;
+
+    #[derive(Demo, PartialEq, Debug)]
+    #[Demo(into_for = "String, std::path::PathBuf")]
+    pub struct Foo {
+        pub x: String,
+        pub y: PathBuf,
+        pub z: Vec<u8>,
+        #[Demo(no_into)]
+        pub w: String,
+    }
+
+    assert_eq!(
+        Foo::demo("bar", "baz", vec![1], "qux".to_owned()),
+        Foo {
+            x: "bar".to_owned(),
+            y: PathBuf::from("baz"),
+            z: vec![1],
+            w: "qux".to_owned(),
+        }
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_struct_with_into_generic() {
+    #[derive(Demo, PartialEq, Debug)]
+    pub struct Foo<T> {
+        #[Demo(into_generic)]
+        pub value: T,
+        #[Demo(into_generic)]
+        pub name: String,
+    }
+
+    assert_eq!(
+        Foo::<i64>::demo(1i32, "bar"),
+        Foo {
+            value: 1i64,
+            name: "bar".to_owned(),
+        }
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_struct_with_from_as_ref_to_owned() {
+    use std::path::{Path, PathBuf};
+
+    #[derive(Demo, PartialEq, Debug)]
+    pub struct Foo {
+        #[Demo(from = "u8")]
+        pub a: u64,
+        #[Demo(as_ref)]
+        pub b: String,
+        #[Demo(as_ref)]
+        pub c: PathBuf,
+        #[Demo(to_owned)]
+        pub d: String,
+        #[Demo(to_owned)]
+        pub e: Vec<i32>,
+        #[Demo(to_owned = "str")]
+        pub f: String,
+        #[Demo(to_owned)]
+        pub g: (i32, i32),
+    }
+
+    assert_eq!(
+        Foo::demo(
+            1,
+            String::from("b"),
+            Path::new("c"),
+            "d",
+            &[1, 2],
+            "f",
+            &(3, 4)
+        ),
+        Foo {
+            a: 1,
+            b: "b".to_owned(),
+            c: PathBuf::from("c"),
+            d: "d".to_owned(),
+            e: vec![1, 2],
+            f: "f".to_owned(),
+            g: (3, 4),
+        }
+    );
+}
+
 #[cfg(feature = "std")]
 #[test]
 fn test_struct_with_into_iter() {
@@ -225,6 +341,65 @@ fn test_struct_with_into_iter() {
     assert_eq!(Foo::demo(None), Foo { values: vec![] });
 }
 
+#[cfg(feature = "std")]
+#[test]
+fn test_struct_with_optional() {
+    #[derive(Demo, PartialEq, Debug)]
+    pub struct Foo {
+        #[Demo(optional)]
+        pub x: String,
+        #[Demo(optional = "42")]
+        pub y: i32,
+    }
+
+    assert_eq!(
+        Foo::demo(None, None),
+        Foo {
+            x: String::new(),
+            y: 42
+        }
+    );
+
+    assert_eq!(
+        Foo::demo(Some("bar".to_owned()), Some(1)),
+        Foo {
+            x: "bar".to_owned(),
+            y: 1
+        }
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_struct_with_inferred_into_iter() {
+    use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+    #[derive(Demo, PartialEq, Debug)]
+    pub struct Foo {
+        #[Demo(into_iter)]
+        pub a: Vec<bool>,
+        #[Demo(into_iter)]
+        pub b: VecDeque<u8>,
+        #[Demo(into_iter)]
+        pub c: BTreeSet<i32>,
+        #[Demo(into_iter)]
+        pub d: Box<[char]>,
+        #[Demo(into_iter)]
+        pub e: BTreeMap<&'static str, u8>,
+    }
+
+    assert_eq!(
+        Foo::demo([true], Some(1), [3, 2, 3], "ab".chars(), [("x", 1)]),
+        Foo {
+            a: vec![true],
+            b: VecDeque::from(vec![1]),
+            c: BTreeSet::from([2, 3]),
+            d: Box::new(['a', 'b']),
+            e: BTreeMap::from([("x", 1)]),
+        }
+    );
+}
+
 /// A struct where fields have explicitly provided defaults.
 #[derive(Demo, PartialEq, Debug)]
 pub struct Fred {
@@ -248,6 +423,115 @@ fn test_struct_with_values() {
     );
 }
 
+/// A struct where fields have defaults and types given without string literals.
+#[derive(Demo, PartialEq, Debug)]
+pub struct Garply {
+    #[Demo(value = 1 + 2)]
+    pub x: i32,
+    #[Demo(value = vec![-42, 42])]
+    pub y: Vec<i8>,
+    #[Demo(value = "Garply".len())]
+    pub z: usize,
+    #[Demo(into_iter = (u8, char))]
+    pub w: Vec<(u8, char)>,
+    #[Demo(optional = i64::MAX)]
+    pub v: i64,
+    #[Demo(value = if cfg!(test) { 'y' } else { 'n' }, skip)]
+    pub u: char,
+}
+
+#[test]
+fn test_struct_with_unquoted_values() {
+    let x = Garply::demo([(1, 'a')], None);
+    assert_eq!(
+        x,
+        Garply {
+            x: 3,
+            y: vec![-42, 42],
+            z: 6,
+            w: vec![(1, 'a')],
+            v: i64::MAX,
+            u: 'y'
+        }
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_struct_with_dependent_values() {
+    /// A struct where defaults are computed from earlier fields.
+    #[derive(Demo, PartialEq, Debug)]
+    pub struct Waldo {
+        #[Demo(into_iter)]
+        pub data: Vec<u8>,
+        #[Demo(value = data.len())]
+        pub len: usize,
+        pub scale: usize,
+        #[Demo(optional = len * scale)]
+        pub total: usize,
+    }
+
+    assert_eq!(
+        Waldo::demo([1, 2, 3], 2, None),
+        Waldo {
+            data: vec![1, 2, 3],
+            len: 3,
+            scale: 2,
+            total: 6
+        }
+    );
+
+    /// A tuple struct where defaults are computed from earlier fields.
+    #[derive(Demo, PartialEq, Debug)]
+    pub struct Fum(pub String, #[Demo(value = "f0.to_uppercase()")] pub String);
+
+    assert_eq!(
+        Fum::demo("fum".to_owned()),
+        Fum("fum".to_owned(), "FUM".to_owned())
+    );
+
+    /// A struct whose value refers to an `optional` field declared before it.
+    #[derive(Demo, PartialEq, Debug)]
+    pub struct Fie {
+        #[Demo(optional)]
+        pub n: u8,
+        #[Demo(value = n + 1)]
+        pub next: u8,
+    }
+
+    assert_eq!(Fie::demo(None), Fie { n: 0, next: 1 });
+    assert_eq!(Fie::demo(Some(2)), Fie { n: 2, next: 3 });
+
+    /// A point used as a default value.
+    #[derive(PartialEq, Debug)]
+    pub struct Point {
+        pub x: i32,
+        pub y: i32,
+    }
+
+    /// A struct whose values use names of later fields as struct keys and closure
+    /// parameters.
+    #[derive(Demo, PartialEq, Debug)]
+    pub struct Fee {
+        #[Demo(value = Point { x: 0, y: 0 })]
+        pub origin: Point,
+        #[Demo(value = [1, 2].iter().map(|x| x * 2).sum())]
+        pub sum: i32,
+        pub x: i32,
+        pub y: i32,
+    }
+
+    assert_eq!(
+        Fee::demo(1, 2),
+        Fee {
+            origin: Point { x: 0, y: 0 },
+            sum: 6,
+            x: 1,
+            y: 2
+        }
+    );
+}
+
 #[cfg(feature = "std")]
 #[test]
 fn test_struct_mixed_defaults() {
@@ -270,6 +554,46 @@ fn test_struct_mixed_defaults() {
     );
 }
 
+#[cfg(feature = "std")]
+#[test]
+fn test_struct_with_skip() {
+    /// A struct with fields that are not part of the demo surface.
+    #[derive(Demo, PartialEq, Debug)]
+    pub struct Plugh {
+        pub x: i32,
+        #[Demo(skip)]
+        pub y: Vec<u8>,
+        #[Demo(skip, value = "42")]
+        pub z: u32,
+    }
+
+    let x = Plugh::demo(1);
+    assert_eq!(
+        x,
+        Plugh {
+            x: 1,
+            y: vec![],
+            z: 42
+        }
+    );
+}
+
+#[test]
+fn test_struct_with_merged_attributes() {
+    /// A struct whose field options are spread over several attributes.
+    #[derive(Demo, PartialEq, Debug)]
+    pub struct Xyzzy {
+        #[Demo(skip)]
+        #[Demo(value = "7")]
+        pub x: u32,
+        #[Demo(into)]
+        pub y: i64,
+    }
+
+    let x = Xyzzy::demo(3i32);
+    assert_eq!(x, Xyzzy { x: 7, y: 3 });
+}
+
 #[cfg(feature = "std")]
 #[test]
 fn test_struct_phantom_data() {
@@ -349,6 +673,724 @@ fn test_tuple_phantom_data() {
     assert_eq!(x, Sponge(42, PhantomData));
 }
 
+/// A struct normalised by a hook after construction.
+#[derive(Demo, PartialEq, Debug)]
+#[Demo(init = "Hook::normalise")]
+pub struct Hook {
+    pub name: &'static str,
+    #[Demo(default)]
+    pub len: usize,
+}
+
+impl Hook {
+    fn normalise(&mut self) {
+        self.name = self.name.trim();
+        self.len = self.name.len();
+    }
+}
+
+#[test]
+fn test_struct_with_init_hook() {
+    let x = Hook::demo("  hook ");
+    assert_eq!(
+        x,
+        Hook {
+            name: "hook",
+            len: 4
+        }
+    );
+}
+
+/// An enum whose variants are rebuilt by a fallible hook.
+#[derive(Demo, PartialEq, Debug)]
+#[Demo(init_with = Shape::validate, error = &'static str)]
+pub enum Shape {
+    Circle(i32),
+    Rect { w: i32, h: i32 },
+}
+
+impl Shape {
+    fn validate(self) -> Result<Self, &'static str> {
+        match self {
+            Shape::Circle(r) if r < 0 => Err("negative radius"),
+            Shape::Circle(r) => Ok(Shape::Circle(r)),
+            Shape::Rect { w, h } if w < 0 || h < 0 => Err("negative size"),
+            Shape::Rect { w, h } => Ok(Shape::Rect {
+                w: w.max(h),
+                h: w.min(h),
+            }),
+        }
+    }
+}
+
+#[test]
+fn test_enum_with_fallible_init_hook() {
+    assert_eq!(Shape::demo_circle(1), Ok(Shape::Circle(1)));
+    assert_eq!(Shape::demo_circle(-1), Err("negative radius"));
+    assert_eq!(Shape::demo_rect(1, 2), Ok(Shape::Rect { w: 2, h: 1 }));
+    assert_eq!(Shape::demo_rect(1, -2), Err("negative size"));
+}
+
+/// Checks that a value is positive.
+fn positive(value: &i32) -> Result<(), &'static str> {
+    match *value > 0 {
+        true => Ok(()),
+        false => Err("not positive"),
+    }
+}
+
+/// A struct with a validated field.
+#[derive(Demo, PartialEq, Debug)]
+#[Demo(error = &'static str)]
+pub struct Validated {
+    #[Demo(validate = positive)]
+    pub count: i32,
+    #[Demo(value = count * 2, validate = positive)]
+    pub double: i32,
+}
+
+/// Panics on an empty string.
+fn non_empty(value: &&str) {
+    assert!(!value.is_empty(), "empty");
+}
+
+/// A tuple struct with a field validated by a panicking check.
+#[derive(Demo, PartialEq, Debug)]
+pub struct ValidatedTuple(#[Demo(validate = non_empty)] pub &'static str);
+
+#[test]
+fn test_validated_fields() {
+    assert_eq!(
+        Validated::demo(2),
+        Ok(Validated {
+            count: 2,
+            double: 4
+        })
+    );
+    assert_eq!(Validated::demo(0), Err("not positive"));
+    assert_eq!(ValidatedTuple::demo("a"), ValidatedTuple("a"));
+    assert!(std::panic::catch_unwind(|| ValidatedTuple::demo("")).is_err());
+}
+
+/// A struct with an explicit argument list.
+#[derive(Demo, PartialEq, Debug)]
+#[Demo(args = "c, a")]
+pub struct Ordered {
+    pub a: i32,
+    #[Demo(default)]
+    pub b: i32,
+    pub c: &'static str,
+}
+
+/// A tuple struct with ordering keys.
+#[derive(Demo, PartialEq, Debug)]
+pub struct OrderedTuple(
+    #[Demo(order = 3)] pub u8,
+    #[Demo(order = -1)] pub &'static str,
+    #[Demo(order = 0)] pub bool,
+);
+
+/// An enum with an explicit argument list on a variant.
+#[derive(Demo, PartialEq, Debug)]
+pub enum OrderedEnum {
+    #[Demo(args = "1, 0")]
+    Pair(u8, &'static str),
+    Named {
+        #[Demo(order = 1)]
+        x: u8,
+        #[Demo(order = 0)]
+        y: bool,
+    },
+}
+
+#[test]
+fn test_argument_order() {
+    assert_eq!(Ordered::demo("c", 1), Ordered { a: 1, b: 0, c: "c" });
+    assert_eq!(
+        OrderedTuple::demo("b", false, 1),
+        OrderedTuple(1, "b", false)
+    );
+    assert_eq!(OrderedEnum::demo_pair("b", 1), OrderedEnum::Pair(1, "b"));
+    assert_eq!(
+        OrderedEnum::demo_named(true, 2),
+        OrderedEnum::Named { x: 2, y: true }
+    );
+}
+
+/// An identifier type used to derive parameter names.
+#[derive(PartialEq, Debug)]
+pub struct UserId(pub u32);
+
+/// A tuple struct with named constructor parameters.
+#[derive(Demo, PartialEq, Debug)]
+pub struct Renamed(
+    #[Demo(arg)] pub UserId,
+    #[Demo(arg = "label", into)] pub String,
+    #[Demo(value = "label.len() + user_id.0 as usize")] pub usize,
+);
+
+/// A tuple struct with a parameter named after a keyword.
+#[derive(Demo, PartialEq, Debug)]
+pub struct RenamedKeyword(#[Demo(arg)] pub Box<u8>, #[Demo(value = *r#box + 1)] pub u8);
+
+/// A struct with a renamed constructor parameter.
+#[derive(Demo, PartialEq, Debug)]
+pub struct RenamedField {
+    #[Demo(arg = count)]
+    pub n: u32,
+    #[Demo(value = n * 2)]
+    pub double: u32,
+}
+
+#[test]
+fn test_renamed_arguments() {
+    assert_eq!(
+        Renamed::demo(UserId(2), "abc"),
+        Renamed(UserId(2), "abc".to_owned(), 5)
+    );
+    assert_eq!(RenamedField::demo(3), RenamedField { n: 3, double: 6 });
+    assert_eq!(
+        RenamedKeyword::demo(Box::new(1)),
+        RenamedKeyword(Box::new(1), 2)
+    );
+}
+
+/// A struct with documented constructor parameters.
+#[derive(Demo, PartialEq, Debug)]
+#[Demo(doc = "Appended to the generated docs.")]
+pub struct Documented {
+    /// The answer.
+    pub x: i32,
+    #[Demo(doc = "A name.", into)]
+    pub y: String,
+}
+
+/// An enum with custom constructor docs for a variant.
+#[derive(Demo, PartialEq, Debug)]
+pub enum DocumentedEnum {
+    #[Demo(replace_doc = "Constructs nothing in particular.")]
+    Nothing,
+}
+
+#[test]
+fn test_constructor_docs() {
+    assert_eq!(
+        Documented::demo(42, "y"),
+        Documented {
+            x: 42,
+            y: "y".to_owned()
+        }
+    );
+    assert_eq!(DocumentedEnum::demo_nothing(), DocumentedEnum::Nothing);
+}
+
+/// An enum with forwarded variant attributes.
+#[derive(Demo, PartialEq, Debug)]
+#[Demo(attrs = "#[inline] #[allow(clippy::inline_always)]", must_use = false)]
+pub enum Forwarded {
+    Current,
+    #[deprecated(note = "use `Current`")]
+    Old,
+    #[cfg(any())]
+    Never,
+}
+
+/// A deprecated struct.
+#[deprecated(note = "use `Bar`")]
+#[derive(Demo, PartialEq, Debug)]
+#[Demo(patch, parts)]
+pub struct Deprecated(pub i32);
+
+#[test]
+#[allow(deprecated)]
+fn test_forwarded_attributes() {
+    Forwarded::demo_current();
+    assert_eq!(Forwarded::demo_current(), Forwarded::Current);
+    assert_eq!(Forwarded::demo_old(), Forwarded::Old);
+    assert_eq!(Deprecated::demo(1), Deprecated(1));
+    assert_eq!(
+        Deprecated::demo_patched(DeprecatedDemoPatch::default()),
+        Deprecated(0)
+    );
+    assert_eq!(Deprecated::from((2,)).into_demo_parts(), (2,));
+}
+
+/// A struct with fields gated by `#[cfg]`.
+#[derive(Demo, PartialEq, Debug)]
+pub struct Gated {
+    pub x: i32,
+    #[cfg(not(test))]
+    pub never: i32,
+    #[cfg(test)]
+    #[Demo(into)]
+    pub always: String,
+}
+
+/// A tuple struct with fields gated by `#[cfg]`.
+#[derive(Demo, PartialEq, Debug)]
+pub struct GatedTuple(#[cfg(not(test))] pub u8, pub i32, #[cfg(test)] pub bool);
+
+/// A struct with a constructor for each configuration.
+#[derive(Demo, PartialEq, Debug)]
+#[Demo(cfg_variants)]
+pub struct GatedVariants {
+    pub x: i32,
+    #[cfg(test)]
+    #[Demo(optional = 8)]
+    pub y: u8,
+    #[cfg(test)]
+    pub z: bool,
+}
+
+/// A struct with a constructor for each configuration and an explicit argument list.
+#[derive(Demo, PartialEq, Debug)]
+#[Demo(cfg_variants, args = "port, name")]
+pub struct GatedOrdered {
+    pub name: &'static str,
+    #[cfg(test)]
+    pub port: u16,
+}
+
+/// An enum with a constructor for each configuration.
+#[derive(Demo, PartialEq, Debug)]
+#[Demo(cfg_variants)]
+pub enum GatedEnum {
+    Tuple(i32, #[cfg(test)] u8),
+}
+
+#[test]
+fn test_cfg_gated_fields() {
+    assert_eq!(
+        Gated::demo(1, "a"),
+        Gated {
+            x: 1,
+            always: "a".to_owned()
+        }
+    );
+    assert_eq!(GatedTuple::demo(1, true), GatedTuple(1, true));
+    assert_eq!(
+        GatedVariants::demo(1),
+        GatedVariants {
+            x: 1,
+            y: 8,
+            z: false
+        }
+    );
+    assert_eq!(
+        GatedVariants::demo_full(1, Some(2), true),
+        GatedVariants {
+            x: 1,
+            y: 2,
+            z: true
+        }
+    );
+    assert_eq!(GatedOrdered::demo("a"), GatedOrdered { name: "a", port: 0 });
+    assert_eq!(
+        GatedOrdered::demo_full(80, "a"),
+        GatedOrdered {
+            name: "a",
+            port: 80
+        }
+    );
+    assert_eq!(GatedEnum::demo_tuple(1), GatedEnum::Tuple(1, 0));
+    assert_eq!(GatedEnum::demo_tuple_full(1, 2), GatedEnum::Tuple(1, 2));
+}
+
+/// An alias of `PhantomData`.
+pub type Marker<T> = std::marker::PhantomData<T>;
+
+mod fake {
+    /// A type that is not a marker despite its name.
+    #[derive(PartialEq, Debug)]
+    pub struct PhantomData(pub u8);
+}
+
+/// A struct with zero-sized marker fields.
+#[derive(Demo, PartialEq, Debug)]
+pub struct Markers<T> {
+    pub x: u8,
+    #[Demo(phantom)]
+    pub marker: Marker<T>,
+    pub pinned: std::marker::PhantomPinned,
+    pub unit: (),
+    pub fake: fake::PhantomData,
+}
+
+/// A struct with a field that only looks like a marker.
+#[derive(Demo, PartialEq, Debug)]
+pub struct NotMarker(#[Demo(not_phantom)] pub ());
+
+#[test]
+fn test_marker_fields() {
+    assert_eq!(
+        Markers::<String>::demo(1, fake::PhantomData(2)),
+        Markers {
+            x: 1,
+            marker: std::marker::PhantomData,
+            pinned: std::marker::PhantomPinned,
+            unit: (),
+            fake: fake::PhantomData(2),
+        }
+    );
+    assert_eq!(NotMarker::demo(()), NotMarker(()));
+}
+
+/// Options reported as having no effect, with the warnings silenced.
+#[allow(deprecated)]
+mod ineffective {
+    use derive_demo::Demo;
+    use std::marker::PhantomData;
+
+    /// A struct with options that have no effect.
+    #[derive(Demo, PartialEq, Debug)]
+    #[Demo(into_for = "String")]
+    pub struct Ineffective<T> {
+        #[Demo(value = "PhantomData")]
+        pub marker: PhantomData<T>,
+        #[Demo(into)]
+        pub t: T,
+        #[Demo(into)]
+        pub s: String,
+        #[Demo(no_into)]
+        pub n: u8,
+    }
+}
+
+#[test]
+fn test_ineffective_options() {
+    use ineffective::Ineffective;
+    assert_eq!(
+        Ineffective::demo(1, "s", 2),
+        Ineffective {
+            marker: std::marker::PhantomData,
+            t: 1,
+            s: "s".to_owned(),
+            n: 2,
+        }
+    );
+}
+
+/// A struct whose constructor bounds are inferred from the fields.
+#[derive(Demo, PartialEq, Debug)]
+pub struct Inferred<T, U, V, W> {
+    #[Demo(default)]
+    pub t: T,
+    #[Demo(into_iter = "u8")]
+    pub u: U,
+    #[Demo(optional)]
+    pub v: Vec<V>,
+    #[Demo(optional)]
+    pub w: Option<W>,
+}
+
+/// A struct with an explicit constructor bound.
+#[derive(Demo, PartialEq, Debug)]
+pub struct Bounded<T> {
+    #[Demo(default, bound = "T: Default + Clone")]
+    pub t: T,
+}
+
+#[test]
+fn test_inferred_bounds() {
+    assert_eq!(
+        Inferred::<u8, Vec<u8>, u8, u8>::demo([1], None, None),
+        Inferred {
+            t: 0,
+            u: vec![1],
+            v: vec![],
+            w: None,
+        }
+    );
+    assert_eq!(Bounded::<u8>::demo(), Bounded { t: 0 });
+}
+
+/// A generic struct with constructors for concrete instances.
+#[derive(Demo, PartialEq, Debug)]
+#[Demo(
+    for = "Instances<u32, ()>",
+    for(ty = "Instances<'static, String, u8>", name = "demo_string")
+)]
+pub struct Instances<'a, T, P> {
+    pub x: &'a str,
+    pub y: std::marker::PhantomData<P>,
+    #[Demo(into_iter)]
+    pub z: Vec<T>,
+}
+
+/// A generic enum with constructors for a concrete instance next to the generic ones.
+#[derive(Demo, PartialEq, Debug)]
+#[Demo(for(ty = "ArrayEnum<u8, 2>", name = "demo_pair"))]
+pub enum ArrayEnum<T, const N: usize> {
+    Array([T; N]),
+}
+
+#[test]
+fn test_instances() {
+    assert_eq!(
+        Instances::demo("a", [1]),
+        Instances {
+            x: "a",
+            y: std::marker::PhantomData::<()>,
+            z: vec![1u32],
+        }
+    );
+    assert_eq!(
+        Instances::demo_string("b", ["c".to_owned()]),
+        Instances {
+            x: "b",
+            y: std::marker::PhantomData::<u8>,
+            z: vec!["c".to_owned()],
+        }
+    );
+    assert_eq!(ArrayEnum::demo_pair_array([1, 2]), ArrayEnum::Array([1, 2]));
+    assert_eq!(
+        ArrayEnum::<bool, 1>::demo_array([true]),
+        ArrayEnum::Array([true])
+    );
+}
+
+/// A struct with array fields filled from iterators.
+#[derive(Demo, PartialEq, Debug)]
+pub struct Arrays<const N: usize> {
+    #[Demo(into_iter)]
+    pub x: [u8; N],
+    #[Demo(into_iter = "char")]
+    pub y: [char; 2],
+}
+
+/// A struct whose constructor fails on arrays of the wrong length.
+#[derive(Demo, PartialEq, Debug)]
+#[Demo(error = String)]
+pub struct CheckedArray {
+    #[Demo(into_iter, len_check = "error")]
+    pub x: [u8; 2],
+}
+
+#[test]
+fn test_array_into_iter() {
+    assert_eq!(
+        Arrays::<3>::demo(1..4, "ab".chars()),
+        Arrays {
+            x: [1, 2, 3],
+            y: ['a', 'b'],
+        }
+    );
+    assert_eq!(CheckedArray::demo([1, 2]), Ok(CheckedArray { x: [1, 2] }));
+    assert_eq!(
+        CheckedArray::demo([1]),
+        Err("wrong number of items for field `x`".to_owned())
+    );
+    assert!(CheckedArray::demo([1, 2, 3]).is_err());
+}
+
+#[test]
+#[should_panic(expected = "wrong number of items for field `y`")]
+fn test_array_into_iter_panics() {
+    let _ = Arrays::<1>::demo([1], "abc".chars());
+}
+
+/// A struct with fields behind smart pointers.
+#[derive(Demo, Debug)]
+pub struct Pointers {
+    #[Demo(boxed)]
+    pub a: Box<u32>,
+    #[Demo(arc)]
+    pub b: std::sync::Arc<std::sync::Mutex<String>>,
+    #[Demo(rc)]
+    pub c: std::rc::Rc<std::cell::RefCell<Vec<u8>>>,
+    #[Demo(cow)]
+    pub d: std::borrow::Cow<'static, str>,
+}
+
+#[test]
+fn test_pointer_fields() {
+    let x = Pointers::demo(1, "b".to_owned(), vec![2], "d");
+    assert_eq!(*x.a, 1);
+    assert_eq!(*x.b.lock().unwrap(), "b");
+    assert_eq!(*x.c.borrow(), [2]);
+    assert_eq!(x.d, "d");
+}
+
+/// A struct with constructors returning smart pointers.
+#[derive(Demo, PartialEq, Debug)]
+#[Demo(wrap = "Box, Arc, Rc, Pin<Box>")]
+pub struct Wrapped {
+    pub x: i32,
+}
+
+/// An enum with fallible constructors returning smart pointers.
+#[derive(Demo, PartialEq, Debug)]
+#[Demo(wrap = "Box", init_with = Self::check, error = String)]
+pub enum WrappedEnum {
+    Value(i32),
+}
+
+impl WrappedEnum {
+    fn check(self) -> Result<Self, String> {
+        match self {
+            WrappedEnum::Value(x) if x < 0 => Err("negative".to_owned()),
+            value => Ok(value),
+        }
+    }
+}
+
+#[test]
+fn test_wrapped_constructors() {
+    assert_eq!(Wrapped::demo_boxed(1), Box::new(Wrapped { x: 1 }));
+    assert_eq!(Wrapped::demo_arc(2), std::sync::Arc::new(Wrapped { x: 2 }));
+    assert_eq!(Wrapped::demo_rc(3), std::rc::Rc::new(Wrapped { x: 3 }));
+    assert_eq!(Wrapped::demo_pinned(4), Box::pin(Wrapped { x: 4 }));
+    assert_eq!(
+        WrappedEnum::demo_value_boxed(1),
+        Ok(Box::new(WrappedEnum::Value(1)))
+    );
+    assert_eq!(
+        WrappedEnum::demo_value_boxed(-1),
+        Err("negative".to_owned())
+    );
+}
+
+/// A struct that converts from and to its constructor arguments.
+#[derive(Demo, PartialEq, Debug, Clone)]
+#[Demo(parts, args = "b, a, d")]
+pub struct Parts<T> {
+    pub a: T,
+    #[Demo(optional)]
+    pub b: u8,
+    #[Demo(default)]
+    pub c: bool,
+    #[Demo(to_owned)]
+    pub d: String,
+}
+
+/// A tuple struct that converts from its constructor arguments fallibly.
+#[derive(Demo, PartialEq, Debug)]
+#[Demo(parts, init = Self::check, error = String)]
+pub struct CheckedParts(#[Demo(boxed)] pub Box<i32>);
+
+impl CheckedParts {
+    fn check(&mut self) -> Result<(), String> {
+        if *self.0 < 0 {
+            return Err("negative".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_demo_parts() {
+    let x = Parts::demo(Some(1), 2, "d");
+    assert_eq!(x.clone().into_demo_parts(), (1, 2, "d".to_owned()));
+    let parts: (u8, i32, String) = x.clone().into();
+    assert_eq!(Parts::from(parts), x);
+    assert_eq!(
+        CheckedParts::try_from((Box::new(1),)),
+        Ok(CheckedParts(Box::new(1)))
+    );
+    assert!(CheckedParts::try_from((Box::new(-1),)).is_err());
+}
+
+/// A struct with a constructor that copies the fields not overridden from a base value.
+#[derive(Demo, PartialEq, Debug)]
+pub struct Fixture {
+    #[Demo(override)]
+    pub id: u32,
+    pub name: String,
+    #[Demo(override, optional = 7)]
+    pub level: u8,
+}
+
+/// A tuple struct with a constructor that copies the fields not overridden.
+#[derive(Demo, PartialEq, Debug)]
+pub struct TupleFixture(pub u8, #[Demo(override, into)] pub String);
+
+#[test]
+fn test_update_constructor() {
+    let base = Fixture::demo(1, "base".to_owned(), Some(2));
+    assert_eq!(
+        Fixture::demo_from(base, 3, None),
+        Fixture {
+            id: 3,
+            name: "base".to_owned(),
+            level: 7,
+        }
+    );
+    let base = TupleFixture::demo(1, "a");
+    assert_eq!(
+        TupleFixture::demo_from(base, "b"),
+        TupleFixture(1, "b".to_owned())
+    );
+}
+
+/// A struct with a patch type for fixtures.
+#[derive(Demo, PartialEq, Debug)]
+#[Demo(patch)]
+pub struct Patched<'a, T> {
+    pub name: &'a str,
+    pub value: Vec<T>,
+    #[Demo(value = "value.len()")]
+    pub len: usize,
+    #[Demo(optional = 3)]
+    pub tries: u8,
+    #[Demo(skip)]
+    pub hits: u32,
+    pub marker: std::marker::PhantomData<u8>,
+}
+
+/// A struct with a patch type and a renamed constructor parameter.
+#[derive(Demo, PartialEq, Debug)]
+#[Demo(patch)]
+pub struct PatchedRenamed {
+    #[Demo(arg = count)]
+    pub n: u32,
+    #[Demo(value = count * 2)]
+    pub double: u32,
+}
+
+/// A tuple struct with a patch type for fixtures.
+#[derive(Demo, PartialEq, Debug)]
+#[Demo(patch)]
+pub struct PatchedTuple(pub u8, #[Demo(value = 5)] pub u8);
+
+#[test]
+fn test_demo_patch() {
+    let mut x = Patched::demo_patched(PatchedDemoPatch {
+        value: Some(vec![1, 2]),
+        ..Default::default()
+    });
+    assert_eq!(
+        x,
+        Patched {
+            name: "",
+            value: vec![1, 2],
+            len: 2,
+            tries: 3,
+            hits: 0,
+            marker: std::marker::PhantomData,
+        }
+    );
+    x.apply_demo_patch(PatchedDemoPatch {
+        name: Some("x"),
+        len: Some(0),
+        ..Default::default()
+    });
+    assert_eq!((x.name, x.len), ("x", 0));
+    assert_eq!(
+        PatchedTuple::demo_patched(PatchedTupleDemoPatch {
+            0: Some(1),
+            ..Default::default()
+        }),
+        PatchedTuple(1, 5)
+    );
+    assert_eq!(
+        PatchedRenamed::demo_patched(PatchedRenamedDemoPatch {
+            n: Some(3),
+            ..Default::default()
+        }),
+        PatchedRenamed { n: 3, double: 6 }
+    );
+}
+
 /// An enum with unit variants
 #[derive(Demo, PartialEq, Debug)]
 pub enum Fizz {